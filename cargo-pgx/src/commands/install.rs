@@ -171,6 +171,10 @@ fn copy_sql_files(
         &*additional_features,
         &dest,
         Option::<String>::None,
+        Option::<String>::None,
+        Option::<String>::None,
+        false,
+        false,
         None,
         false,
         true,
@@ -22,6 +22,10 @@ pub(crate) fn generate_schema(
     additional_features: &[&str],
     path: impl AsRef<std::path::Path>,
     dot: Option<impl AsRef<std::path::Path>>,
+    manifest: Option<impl AsRef<std::path::Path>>,
+    previous_manifest: Option<impl AsRef<std::path::Path>>,
+    if_not_exists: bool,
+    pretty: bool,
     log_level: Option<String>,
     force_default: bool,
     manual: bool,
@@ -248,6 +252,20 @@ pub(crate) fn generate_schema(
         command.arg("--dot");
         command.arg(dot.as_ref());
     }
+    if let Some(manifest) = manifest {
+        command.arg("--manifest");
+        command.arg(manifest.as_ref());
+    }
+    if let Some(previous_manifest) = previous_manifest {
+        command.arg("--previous-manifest");
+        command.arg(previous_manifest.as_ref());
+    }
+    if if_not_exists {
+        command.arg("--if-not-exists");
+    }
+    if pretty {
+        command.arg("--pretty");
+    }
     command.env(
         "PGX_SQL_ENTITY_SYMBOLS",
         fns_to_call
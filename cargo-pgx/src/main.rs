@@ -241,7 +241,11 @@ fn do_it() -> std::result::Result<(), std::io::Error> {
                 } else {
                     None
                 };
+                let manifest = schema.value_of("manifest");
+                let previous_manifest = schema.value_of("previous-manifest");
                 let is_release = schema.is_present("release");
+                let if_not_exists = schema.is_present("if-not-exists");
+                let pretty = schema.is_present("pretty");
 
                 let log_level = if let Ok(log_level) = std::env::var("RUST_LOG") {
                     Some(log_level)
@@ -282,7 +286,18 @@ fn do_it() -> std::result::Result<(), std::io::Error> {
                 let manual = schema.is_present("manual");
 
                 schema::generate_schema(
-                    &pg_config, is_release, &features, &out, dot, log_level, default, manual,
+                    &pg_config,
+                    is_release,
+                    &features,
+                    &out,
+                    dot,
+                    manifest,
+                    previous_manifest,
+                    if_not_exists,
+                    pretty,
+                    log_level,
+                    default,
+                    manual,
                 )
             }
             ("get", Some(get)) => {
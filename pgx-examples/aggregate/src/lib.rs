@@ -43,12 +43,6 @@ impl Default for IntegerAvgState {
 }
 
 impl IntegerAvgState {
-    fn acc(&self, v: i32) -> PgVarlena<Self> {
-        let mut new = PgVarlena::<Self>::new();
-        new.sum = self.sum + v;
-        new.n = self.n + 1;
-        new
-    }
     fn finalize(&self) -> i32 {
         self.sum / self.n
     }
@@ -59,7 +53,10 @@ fn integer_avg_state_func(
     internal_state: PgVarlena<IntegerAvgState>,
     next_data_value: i32,
 ) -> PgVarlena<IntegerAvgState> {
-    internal_state.acc(next_data_value)
+    internal_state.modify(|state| {
+        state.sum += next_data_value;
+        state.n += 1;
+    })
 }
 
 #[pg_extern]
@@ -67,6 +64,8 @@ fn integer_avg_final_func(internal_state: PgVarlena<IntegerAvgState>) -> i32 {
     internal_state.finalize()
 }
 
+// Aggregates created this way work with `FILTER (WHERE ...)` without any special support, as
+// Postgres evaluates the filter before calling `sfunc`.
 extension_sql!(
     r#"
     CREATE AGGREGATE DEMOAVG (integer)
@@ -84,14 +83,15 @@ extension_sql!(
 #[pg_schema]
 mod tests {
     use pgx::*;
-    use crate::IntegerAvgState;
+    use crate::{integer_avg_state_func, IntegerAvgState};
 
     #[pg_test]
     fn test_integer_avg_state() {
-        assert_eq!(
-            2,
-            IntegerAvgState::default().acc(1).acc(2).acc(3).finalize()
+        let state = [1, 2, 3].iter().fold(
+            PgVarlena::<IntegerAvgState>::new(),
+            |state, &value| integer_avg_state_func(state, value),
         );
+        assert_eq!(2, state.finalize());
     }
 
     #[pg_test]
@@ -103,6 +103,18 @@ mod tests {
                 .expect("SQL select failed");
         assert_eq!(retval, 2);
     }
+
+    #[pg_test]
+    fn test_integer_avg_state_sql_with_filter() {
+        Spi::run("CREATE TABLE demo_table (value INTEGER);");
+        Spi::run("INSERT INTO demo_table (value) VALUES (1), (2), (3), (4);");
+
+        // Postgres never hands filtered-out rows to the state function, so `1` isn't averaged
+        let retval =
+            Spi::get_one::<i32>("SELECT DEMOAVG(value) FILTER (WHERE value > 1) FROM demo_table;")
+                .expect("SQL select failed");
+        assert_eq!(retval, 3);
+    }
 }
 
 #[cfg(test)]
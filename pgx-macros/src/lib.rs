@@ -42,21 +42,154 @@ pub fn pg_guard(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 /// `#[pg_test]` functions are test functions (akin to `#[test]`), but they run in-process inside
 /// Postgres during `cargo pgx test`.
+///
+/// `#[pg_test(serial)]` marks a test that must not run concurrently with any other `#[pg_test]`,
+/// such as one that depends on state outside of its own transaction.
+///
+/// `#[pg_test(cases = [...])]` makes a table-driven test, whose function takes arguments.  Each
+/// case holds the arguments for one run (as a tuple, if there's more than one) and becomes its own
+/// test, named for the function and the case's position in the list, so it's clear which failed.
+///
+/// ```rust,ignore
+/// #[pg_test(cases = [(vec![1, 2, 3], 2), (vec![10, 20], 15)])]
+/// fn test_avg(values: Vec<i32>, expected: i32) {
+///     // runs as `test_avg_case_0` and `test_avg_case_1`
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn pg_test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let (attr, cases) = match split_test_cases(attr.into()) {
+        Ok(split) => split,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let stream = match cases {
+        Some(cases) => pg_test_cases(attr, item.into(), cases),
+        None => pg_test_impl(attr, item.into()),
+    };
+    match stream {
+        Ok(stream) => stream.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Remove `cases = [...]` from the `#[pg_test]` attribute, returning what's left and the cases
+fn split_test_cases(
+    attr: proc_macro2::TokenStream,
+) -> syn::Result<(
+    proc_macro2::TokenStream,
+    Option<syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>>,
+)> {
+    let tokens = attr.into_iter().collect::<Vec<_>>();
+    let position = tokens.iter().position(
+        |token| matches!(token, proc_macro2::TokenTree::Ident(ident) if ident == "cases"),
+    );
+    let position = match position {
+        Some(position) => position,
+        None => return Ok((tokens.into_iter().collect(), None)),
+    };
+
+    let cases = match (tokens.get(position + 1), tokens.get(position + 2)) {
+        (Some(proc_macro2::TokenTree::Punct(eq)), Some(proc_macro2::TokenTree::Group(group)))
+            if eq.as_char() == '=' && group.delimiter() == proc_macro2::Delimiter::Bracket =>
+        {
+            syn::parse::Parser::parse2(
+                syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+                group.stream(),
+            )?
+        }
+        _ => {
+            return Err(syn::Error::new(
+                tokens[position].span(),
+                "expected `cases = [...]`",
+            ))
+        }
+    };
+
+    // drop `cases = [...]`, along with the comma that separated it from the other arguments
+    let mut end = position + 3;
+    let mut start = position;
+    match tokens.get(end) {
+        Some(proc_macro2::TokenTree::Punct(comma)) if comma.as_char() == ',' => end += 1,
+        _ if start > 0 => start -= 1,
+        _ => (),
+    }
+    let remaining = tokens[..start]
+        .iter()
+        .chain(tokens[end..].iter())
+        .cloned()
+        .collect();
+    Ok((remaining, Some(cases)))
+}
+
+/// Generate the function itself, and a `#[pg_test]` that calls it for each of `cases`
+fn pg_test_cases(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+    cases: syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let func = syn::parse2::<ItemFn>(item)?;
+    let name = &func.sig.ident;
+    let attrs = &func.attrs;
+    let output = &func.sig.output;
+    let nargs = func.sig.inputs.len();
+    if nargs == 0 {
+        return Err(syn::Error::new(
+            func.sig.span(),
+            "a #[pg_test] with `cases` must take the arguments each case provides",
+        ));
+    }
+
+    let mut stream = func.to_token_stream();
+    for (i, case) in cases.iter().enumerate() {
+        let args = match case {
+            syn::Expr::Tuple(tuple) if nargs > 1 => tuple.elems.iter().cloned().collect(),
+            case => vec![case.clone()],
+        };
+        if args.len() != nargs {
+            return Err(syn::Error::new(
+                case.span(),
+                format!(
+                    "expected {} value(s) for the arguments of `{}`, found {}",
+                    nargs,
+                    name,
+                    args.len()
+                ),
+            ));
+        }
+
+        let case_name = Ident::new(&format!("{}_case_{}", name, i), name.span());
+        stream.extend(pg_test_impl(
+            attr.clone(),
+            quote! {
+                #(#attrs)*
+                fn #case_name() #output {
+                    #name(#(#args),*)
+                }
+            },
+        )?);
+    }
+    Ok(stream)
+}
+
+fn pg_test_impl(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
     let mut stream = proc_macro2::TokenStream::new();
-    let args = parse_extern_attributes(proc_macro2::TokenStream::from(attr.clone()));
+    let args = parse_extern_attributes(attr.clone());
 
     let mut expected_error = None;
-    args.into_iter().for_each(|v| {
-        if let ExternArgs::Error(message) = v {
-            expected_error = Some(message)
-        }
+    let mut serial = false;
+    args.into_iter().for_each(|v| match v {
+        ExternArgs::Error(message) => expected_error = Some(message),
+        ExternArgs::Serial => serial = true,
+        _ => {}
     });
 
     stream.extend(proc_macro2::TokenStream::from(pg_extern(
-        attr,
-        item.clone(),
+        attr.into(),
+        item.clone().into(),
     )));
 
     let expected_error = match expected_error {
@@ -64,7 +197,13 @@ pub fn pg_test(attr: TokenStream, item: TokenStream) -> TokenStream {
         None => quote! {None},
     };
 
-    let ast = parse_macro_input!(item as syn::Item);
+    let run_test = if serial {
+        quote! {pgx_tests::run_serial_test}
+    } else {
+        quote! {pgx_tests::run_test}
+    };
+
+    let ast = syn::parse2::<syn::Item>(item)?;
     match ast {
         Item::Fn(func) => {
             let sql_funcname = func.sig.ident.to_string();
@@ -87,8 +226,12 @@ pub fn pg_test(attr: TokenStream, item: TokenStream) -> TokenStream {
                     let mut options = Vec::new();
                     #att_stream
 
-                    crate::pg_test::setup(options);
-                    pgx_tests::run_test(#sql_funcname, #expected_error, crate::pg_test::postgresql_conf_options())
+                    #run_test(
+                        #sql_funcname,
+                        #expected_error,
+                        crate::pg_test::postgresql_conf_options(),
+                        move || crate::pg_test::setup(options),
+                    )
                 }
             });
         }
@@ -96,7 +239,7 @@ pub fn pg_test(attr: TokenStream, item: TokenStream) -> TokenStream {
         _ => panic!("#[pg_test] can only be applied to top-level functions"),
     }
 
-    stream.into()
+    Ok(stream)
 }
 
 /// Associated macro for `#[pg_test]` to provide context back to your test framework to indicate
@@ -195,8 +338,8 @@ Accepts a String literal, a `name` attribute, and optionally others:
 
 * `name = "item"`: Set the unique identifer to `"item"` for use in `requires` declarations.
 * `requires = [item, item_two]`: References to other `name`s or Rust items which this SQL should be present after.
-* `creates = [ Type(submod::Cust), Enum(Pre), Function(defined)]`: Communicates that this SQL block creates certain entities.
-  Please note it **does not** create matching Rust types.
+* `creates = [ Type(submod::Cust), Enum(Pre), Function(defined)]`: Communicates that this SQL block creates certain entities,
+  so that other items can list them in their `requires`.  Please note it **does not** create matching Rust types.
 * `bootstrap` (**Unique**): Communicates that this is SQL intended to go before all other generated SQL.
 * `finalize` (**Unique**): Communicates that this is SQL intended to go after all other generated SQL.
 
@@ -268,6 +411,46 @@ extension_sql!(r#"
 );
 ```
 
+A function the SQL creates, in PL/pgSQL for example, can be declared with `creates`, so that a
+`#[pg_extern]` can `requires` it.  Generating the SQL fails if there's no `CREATE FUNCTION` for it:
+
+```rust,ignore
+use pgx::*;
+
+extension_sql!(r#"
+    CREATE FUNCTION base_offset() RETURNS integer LANGUAGE plpgsql AS $$
+    BEGIN
+        RETURN 40;
+    END;
+    $$;
+    "#,
+    name = "create_base_offset",
+    creates = [ Function(base_offset) ],
+);
+
+#[pg_extern(requires = [ base_offset ])]
+fn offset(value: i32, offset: default!(i32, "base_offset()")) -> i32 {
+    value + offset
+}
+```
+
+Any `{schema}` in the SQL is replaced with the schema the block is generated into (that of its
+`#[pg_schema]` module, or the extension's schema otherwise):
+
+```rust,ignore
+use pgx_macros::extension_sql;
+
+extension_sql!(r#"
+    CREATE AGGREGATE {schema}.DEMOAVG (numeric) (
+        sfunc = numeric_avg_accum,
+        stype = internal,
+        finalfunc = numeric_avg
+    );
+    "#,
+    name = "demo_avg",
+);
+```
+
 To declare the SQL defines some entity (**Caution:** This is not recommended usage):
 
 ```rust,ignore
@@ -397,7 +580,15 @@ Optionally accepts the following attributes:
 * `parallel_safe`: Corresponds to [`PARALLEL SAFE`](https://www.postgresql.org/docs/current/sql-createfunction.html).
 * `parallel_unsafe`: Corresponds to [`PARALLEL UNSAFE`](https://www.postgresql.org/docs/current/sql-createfunction.html).
 * `parallel_restricted`: Corresponds to [`PARALLEL RESTRICTED`](https://www.postgresql.org/docs/current/sql-createfunction.html).
+* `leakproof`: Corresponds to [`LEAKPROOF`](https://www.postgresql.org/docs/current/sql-createfunction.html).
+  + Must be used with `immutable` or `stable`, and only for functions which reveal nothing about their arguments
+    except through their return value (not even in an error message).
+* `window`: Corresponds to [`WINDOW`](https://www.postgresql.org/docs/current/sql-createfunction.html), for functions
+  called with an `OVER` clause.
+  + The function should take a `fcinfo: pg_sys::FunctionCallInfo` as its last argument, and use
+    `PgWindowObject::from_fcinfo(fcinfo)` to look at the rows of its partition.
 * `no_guard`: Do not use `#[pg_guard]` with the function.
+* `support = path::to::function`: Corresponds to [`SUPPORT`](https://www.postgresql.org/docs/current/xfunc-optimization.html), naming another `#[pg_extern]` with the signature `fn(Internal) -> Internal`.
 
 Functions can accept and return any type which `pgx` supports. `pgx` supports many PostgreSQL types by default.
 New types can be defined via [`macro@PostgresType`] or [`macro@PostgresEnum`].
@@ -490,6 +681,17 @@ fn singlular_floop() -> (name!(a, i32), name!(b, i32)) {
 }
 ```
 
+Either kind of iterator can be wrapped in an `Option`, where `None` returns no rows at all, finishing
+the set-returning function on its first call rather than iterating:
+
+```rust,ignore
+use pgx::*;
+#[pg_extern]
+fn maybe_floop(n: i32) -> Option<impl Iterator<Item = (name!(a, i32), name!(b, i32))>> {
+    if n > 0 { Some((0..n).map(|a| (a, a * 2))) } else { None }
+}
+```
+
 The `name!()` macro may only be used in return position inside the `Item` of an `impl Iterator`.
 
 It accepts 2 arguments:
@@ -522,7 +724,10 @@ pub fn pg_extern(attr: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_extern_attributes(proc_macro2::TokenStream::from(attr.clone()));
 
     let sql_graph_entity_item =
-        sql_entity_graph::PgExtern::new(attr.clone().into(), item.clone().into()).unwrap();
+        match sql_entity_graph::PgExtern::new(attr.clone().into(), item.clone().into()) {
+            Ok(sql_graph_entity_item) => sql_graph_entity_item,
+            Err(e) => return e.to_compile_error().into(),
+        };
 
     let ast = parse_macro_input!(item as syn::Item);
     match ast {
@@ -531,6 +736,38 @@ pub fn pg_extern(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/**
+Declare a function as `#[pg_proc]` to create it as a Postgres [`PROCEDURE`](https://www.postgresql.org/docs/current/sql-createprocedure.html),
+which is run with `CALL`, rather than as a function.
+
+Unlike a function, a procedure called by a top-level `CALL` can end the transaction it runs in,
+and start another, with `Spi::commit()` or `Spi::rollback()`:
+
+```rust,ignore
+use pgx::*;
+#[pg_proc]
+fn load_in_batches(batches: i32) {
+    for batch in 0..batches {
+        Spi::run(&format!("INSERT INTO loaded VALUES ({})", batch));
+        Spi::commit();
+    }
+}
+```
+
+A procedure takes the same arguments as a `#[pg_extern]`, but returns nothing, and only accepts its
+`schema`, `name`, `requires` and `no_guard` attributes.  Procedures require Postgres 11 or later.
+*/
+#[proc_macro_attribute]
+pub fn pg_proc(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let attr = if attr.is_empty() {
+        quote! { procedure }
+    } else {
+        quote! { procedure, #attr }
+    };
+    pg_extern(attr.into(), item)
+}
+
 fn rewrite_item_fn(
     mut func: ItemFn,
     extern_args: HashSet<ExternArgs>,
@@ -679,9 +916,26 @@ Optionally accepts the following attributes:
 
 * `inoutfuncs(some_in_fn, some_out_fn)`: Define custom in/out functions for the type.
 * `pgvarlena_inoutfuncs(some_in_fn, some_out_fn)`: Define custom in/out functions for the `PgVarlena` of this type.
+* `pg_type(storage = "plain", alignment = "double")`: Set the `STORAGE` (`plain`, `external`, `extended` or `main`,
+  defaulting to `extended`) and `ALIGNMENT` (`int4` or `double`) of the generated `CREATE TYPE`.
+* `pg_type(internallength = 32)`: Make this a fixed-length, pass-by-reference type of exactly that many
+  bytes, rather than a varlena.  The struct itself is stored, so it must be `Copy`, exactly that size,
+  and use `#[inoutfuncs]` instead of `Serialize`.  Its `STORAGE` is `plain` and `ALIGNMENT` may also be
+  `char` or `int2`.
+
+```rust,ignore
+use pgx::*;
+#[derive(Copy, Clone, PostgresType)]
+#[pg_type(internallength = 32, alignment = "char")]
+#[inoutfuncs]
+struct Sha256([u8; 32]);
+```
 
 */
-#[proc_macro_derive(PostgresType, attributes(inoutfuncs, pgvarlena_inoutfuncs, requires))]
+#[proc_macro_derive(
+    PostgresType,
+    attributes(inoutfuncs, pgvarlena_inoutfuncs, requires, pg_type)
+)]
 pub fn postgres_type(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as syn::DeriveInput);
 
@@ -775,12 +1029,251 @@ fn impl_postgres_type(ast: DeriveInput) -> proc_macro2::TokenStream {
         });
     }
 
-    let sql_graph_entity_item = sql_entity_graph::PostgresType::from_derive_input(ast).unwrap();
-    sql_graph_entity_item.to_tokens(&mut stream);
+    let name = name.clone();
+    let has_lifetimes = has_lifetimes.is_some();
+    let sql_graph_entity_item = match sql_entity_graph::PostgresType::from_derive_input(ast) {
+        Ok(sql_graph_entity_item) => sql_graph_entity_item,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // a fixed-length type is stored as the bytes of the Rust struct itself, rather than in a varlena
+    if let Some(internal_length) = sql_graph_entity_item.internal_length() {
+        if !args.contains(&PostgresTypeAttribute::InOutFuncs) || has_lifetimes {
+            return syn::Error::new(
+                name.span(),
+                "a #[pg_type(internallength = ...)] type must use #[inoutfuncs] and have no lifetimes",
+            )
+            .to_compile_error();
+        }
 
+        stream.extend(quote! {
+            // the struct is copied byte-for-byte, so it must be exactly `internallength` bytes and `Copy`
+            const _: [(); #internal_length] = [(); std::mem::size_of::<#name>()];
+            const _: fn() = || {
+                fn assert_copy<T: Copy>() {}
+                assert_copy::<#name>();
+            };
+
+            impl pgx::FromDatum for #name {
+                const NEEDS_TYPID: bool = false;
+
+                unsafe fn from_datum(datum: pgx::pg_sys::Datum, is_null: bool, _typoid: pgx::pg_sys::Oid) -> Option<Self> {
+                    if is_null {
+                        None
+                    } else {
+                        Some((datum as *const #name).read_unaligned())
+                    }
+                }
+            }
+
+            impl pgx::IntoDatum for #name {
+                fn into_datum(self) -> Option<pgx::pg_sys::Datum> {
+                    unsafe {
+                        let ptr = pgx::pg_sys::palloc(#internal_length) as *mut #name;
+                        ptr.write_unaligned(self);
+                        Some(ptr as pgx::pg_sys::Datum)
+                    }
+                }
+
+                fn type_oid() -> pgx::pg_sys::Oid {
+                    pgx::rust_regtypein::<Self>()
+                }
+            }
+        });
+    }
+
+    sql_graph_entity_item.to_tokens(&mut stream);
     stream
 }
 
+/**
+Generate a [`SpiRow`](pgx::SpiRow) implementation, so that rows from a SPI query can be read into the
+struct with `SpiTupleTable::into_typed()`.
+
+Each field is read from the column of the same name, which must be of the field's SQL type.
+`Option<T>` fields may be NULL.
+
+```rust,ignore
+use pgx::*;
+#[derive(SpiRow)]
+struct Dog {
+    name: String,
+    age: Option<i32>,
+}
+```
+*/
+#[proc_macro_derive(SpiRow)]
+pub fn spi_row(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+
+    impl_spi_row(ast).into()
+}
+
+fn impl_spi_row(ast: DeriveInput) -> proc_macro2::TokenStream {
+    let fields = match ast.data {
+        Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => fields.named,
+        _ => panic!("#[derive(SpiRow)] can only be applied to structs with named fields"),
+    };
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut field_inits = proc_macro2::TokenStream::new();
+    for field in fields {
+        let ident = field.ident.expect("named field has no name");
+        let column = ident.to_string();
+        let option_inner = match &field.ty {
+            syn::Type::Path(type_path) => {
+                type_path
+                    .path
+                    .segments
+                    .last()
+                    .and_then(|segment| match &segment.arguments {
+                        syn::PathArguments::AngleBracketed(args) if segment.ident == "Option" => {
+                            match args.args.first() {
+                                Some(syn::GenericArgument::Type(inner)) => Some(inner.clone()),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    })
+            }
+            _ => None,
+        };
+
+        field_inits.extend(match option_inner {
+            Some(inner) => quote! {
+                #ident: row.try_by_name::<#inner>(#column)?,
+            },
+            None => {
+                let ty = &field.ty;
+                quote! {
+                    #ident: row.try_by_name::<#ty>(#column)?.ok_or_else(|| {
+                        pgx::SpiRowError::UnexpectedNull { column: #column.to_string() }
+                    })?,
+                }
+            }
+        });
+    }
+
+    quote! {
+        impl #impl_generics pgx::SpiRow for #name #ty_generics #where_clause {
+            fn from_spi_row(row: &pgx::SpiHeapTupleData) -> std::result::Result<Self, pgx::SpiRowError> {
+                Ok(#name {
+                    #field_inits
+                })
+            }
+        }
+    }
+}
+
+/**
+Generate a [`FromDatum`](pgx::FromDatum) implementation for a single-field tuple struct (a
+"newtype"), by delegating to the field's own implementation.
+
+The newtype is read from the same SQL type as its field, so it can be used anywhere the field's
+type can, such as with `Spi::get_one()`.  Derive [`IntoDatum`](macro@IntoDatum) too for the
+reverse conversion.
+
+```rust,ignore
+use pgx::*;
+#[derive(FromDatum, IntoDatum)]
+struct UserId(i64);
+```
+*/
+#[proc_macro_derive(FromDatum)]
+pub fn from_datum(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+
+    impl_from_datum(ast).into()
+}
+
+fn impl_from_datum(ast: DeriveInput) -> proc_macro2::TokenStream {
+    let inner = newtype_field(&ast, "FromDatum");
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics pgx::FromDatum for #name #ty_generics #where_clause {
+            const NEEDS_TYPID: bool = <#inner as pgx::FromDatum>::NEEDS_TYPID;
+
+            unsafe fn from_datum(
+                datum: pgx::pg_sys::Datum,
+                is_null: bool,
+                typoid: pgx::pg_sys::Oid,
+            ) -> Option<Self> {
+                <#inner as pgx::FromDatum>::from_datum(datum, is_null, typoid).map(#name)
+            }
+
+            unsafe fn from_datum_in_memory_context(
+                memory_context: pgx::PgMemoryContexts,
+                datum: pgx::pg_sys::Datum,
+                is_null: bool,
+                typoid: pgx::pg_sys::Oid,
+            ) -> Option<Self> {
+                <#inner as pgx::FromDatum>::from_datum_in_memory_context(
+                    memory_context,
+                    datum,
+                    is_null,
+                    typoid,
+                )
+                .map(#name)
+            }
+        }
+    }
+}
+
+/**
+Generate an [`IntoDatum`](pgx::IntoDatum) implementation for a single-field tuple struct (a
+"newtype"), by delegating to the field's own implementation.
+
+The newtype has the same SQL type as its field.  See [`FromDatum`](macro@FromDatum).
+*/
+#[proc_macro_derive(IntoDatum)]
+pub fn into_datum(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+
+    impl_into_datum(ast).into()
+}
+
+fn impl_into_datum(ast: DeriveInput) -> proc_macro2::TokenStream {
+    let inner = newtype_field(&ast, "IntoDatum");
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics pgx::IntoDatum for #name #ty_generics #where_clause {
+            fn into_datum(self) -> Option<pgx::pg_sys::Datum> {
+                <#inner as pgx::IntoDatum>::into_datum(self.0)
+            }
+
+            fn type_oid() -> pgx::pg_sys::Oid {
+                <#inner as pgx::IntoDatum>::type_oid()
+            }
+
+            fn array_type_oid() -> pgx::pg_sys::Oid {
+                <#inner as pgx::IntoDatum>::array_type_oid()
+            }
+        }
+    }
+}
+
+/// The type of the one field of a tuple struct, for the newtype derives
+fn newtype_field(ast: &DeriveInput, derive: &str) -> syn::Type {
+    match &ast.data {
+        Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unnamed(fields),
+            ..
+        }) if fields.unnamed.len() == 1 => fields.unnamed[0].ty.clone(),
+        _ => panic!(
+            "#[derive({})] can only be applied to tuple structs with a single field",
+            derive
+        ),
+    }
+}
+
 #[proc_macro_derive(PostgresGucEnum, attributes(hidden))]
 pub fn postgres_guc_enum(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as syn::DeriveInput);
@@ -918,6 +1411,10 @@ pub fn postgres_eq(input: TokenStream) -> TokenStream {
 /**
 Generate necessary code using the type in operators like `>`, `<`, `<=`, and `>=`.
 
+When [`macro@PostgresEq`] is also derived, a default `btree` operator class is created too, so the
+type can be used in `ORDER BY`, `GROUP BY`, and (unique) indexes.  It sorts using the type's
+[`Ord`] implementation, which can be written by hand for a custom ordering.
+
 ```rust,ignore
 # use pgx_pg_sys as pg_sys;
 use pgx::*;
@@ -942,6 +1439,9 @@ pub fn postgres_ord(input: TokenStream) -> TokenStream {
 /**
 Generate necessary code for stable hashing the type so it can be used with `USING hash` indexes.
 
+The generated `hash` operator class uses the type's [`Hash`] implementation, which must agree with
+its [`Eq`] implementation.
+
 ```rust,ignore
 # use pgx_pg_sys as pg_sys;
 use pgx::*;
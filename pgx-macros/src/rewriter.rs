@@ -84,12 +84,23 @@ impl PgGuardRewriter {
             Ident::new("result", Span::call_site())
         };
 
-        let func_call = quote! {
-            let #result_var_name = {
-                #rewritten_args
+        let func_call = if entity_submission.map_or(false, |entity| entity.is_procedure()) {
+            // run the procedure in the SPI connection `Spi::commit()` and `Spi::rollback()` need
+            quote! {
+                let #result_var_name = pgx::Spi::in_procedure(fcinfo, || {
+                    #rewritten_args
 
-                #func_name(#arg_list)
-            };
+                    #func_name(#arg_list)
+                });
+            }
+        } else {
+            quote! {
+                let #result_var_name = {
+                    #rewritten_args
+
+                    #func_name(#arg_list)
+                };
+            }
         };
 
         let prolog = quote! {
@@ -113,6 +124,27 @@ impl PgGuardRewriter {
                 true,
             ),
 
+            // the new values of `inout!()` arguments are returned as a single record, not a table
+            CategorizedType::Tuple(types) if PgGuardRewriter::has_inout_args(&func.sig) => {
+                let fields = (0..types.len()).map(syn::Index::from);
+                (
+                    PgGuardRewriter::impl_standard_udf(
+                        func_span,
+                        prolog,
+                        vis,
+                        func_name_wrapper,
+                        generics,
+                        func_call,
+                        quote! {
+                            pgx::pg_return_record(fcinfo, &[#(result.#fields.into_datum()),*])
+                        },
+                        entity_submission,
+                        no_guard,
+                    ),
+                    true,
+                )
+            }
+
             CategorizedType::Tuple(_types) => (
                 PgGuardRewriter::impl_tuple_udf(func, entity_submission.clone()),
                 false,
@@ -563,6 +595,13 @@ impl PgGuardRewriter {
         }
     }
 
+    fn has_inout_args(sig: &Signature) -> bool {
+        sig.inputs.iter().any(|arg| match arg {
+            FnArg::Typed(ty) => pgx_utils::inout_inner_type(&ty.ty).is_some(),
+            FnArg::Receiver(_) => false,
+        })
+    }
+
     pub fn build_func_name(sig: &Signature) -> Ident {
         sig.ident.clone()
     }
@@ -678,11 +717,11 @@ impl FunctionSignatureRewriter {
             }
             ReturnType::Type(_, type_) => {
                 if type_matches(type_, "Option") {
+                    // both `None` and a `Some(_)` that converts to NULL, such as the `Some(None)`
+                    // of an `Option<Option<T>>`, are returned as NULL
                     stream.extend(quote! {
-                        match result {
-                            Some(result) => {
-                                result.into_datum().unwrap_or_else(|| panic!("returned Option<T> was NULL"))
-                            },
+                        match result.into_datum() {
+                            Some(datum) => datum,
                             None => pgx::pg_return_null(fcinfo)
                         }
                     });
@@ -724,6 +763,9 @@ impl FunctionSignatureRewriter {
                     Pat::Ident(ident) => {
                         let name = Ident::new(&format!("{}_", ident.ident), ident.span());
                         let mut type_ = ty.ty.clone();
+                        if let Some(inner) = pgx_utils::inout_inner_type(&type_) {
+                            *type_ = inner;
+                        }
                         let is_option = type_matches(&type_, "Option");
 
                         if have_fcinfo {
@@ -46,6 +46,16 @@ void pgx_ereport(int level, int code, char *message, char *file, int lineno, int
                     errmsg("%s", message), errcontext_msg("%s:%d:%d", file, lineno, colno)));
 }
 
+PGDLLEXPORT void pgx_ereport_detail(int level, int code, char *message, char *detail, char *hint, char *file, int lineno, int colno);
+void pgx_ereport_detail(int level, int code, char *message, char *detail, char *hint, char *file, int lineno, int colno) {
+    ereport(level,
+            (errcode(code),
+                    errmsg("%s", message),
+                    detail ? errdetail("%s", detail) : 0,
+                    hint ? errhint("%s", hint) : 0,
+                    errcontext_msg("%s:%d:%d", file, lineno, colno)));
+}
+
 PGDLLEXPORT void pgx_SET_VARSIZE(struct varlena *ptr, int size);
 void pgx_SET_VARSIZE(struct varlena *ptr, int size) {
     SET_VARSIZE(ptr, size);
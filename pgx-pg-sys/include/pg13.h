@@ -92,4 +92,5 @@
 #include "utils/selfuncs.h"
 #include "utils/snapmgr.h"
 #include "utils/syscache.h"
-#include "utils/typcache.h"
\ No newline at end of file
+#include "utils/typcache.h"
+#include "windowapi.h"
\ No newline at end of file
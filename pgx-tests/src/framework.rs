@@ -4,7 +4,8 @@
 use std::process::{Command, Stdio};
 
 use lazy_static::*;
-use std::sync::{Arc, Mutex};
+use std::cell::Cell;
+use std::sync::{Arc, Condvar, Mutex, Once, RwLock};
 
 use colored::*;
 use pgx::*;
@@ -31,6 +32,63 @@ lazy_static! {
         system_session_id: "NONE".to_string(),
     });
     static ref SHUTDOWN_HOOKS: Mutex<Vec<Box<dyn Fn() + Send>>> = Mutex::new(Vec::new());
+
+    /// Held for reading by every test, and for writing by `#[pg_test(serial)]` tests
+    static ref SERIAL_LOCK: RwLock<()> = RwLock::new(());
+
+    /// Which of the `PGX_TEST_DATABASES` databases a test is running in, signaled whenever one
+    /// is no longer
+    static ref BUSY_TEST_DATABASES: (Mutex<Vec<bool>>, Condvar) =
+        (Mutex::new(vec![false; get_test_database_count()]), Condvar::new());
+
+    /// Guards the [`setup_test_database_once()`] of each of the `PGX_TEST_DATABASES` databases
+    static ref TEST_DATABASE_SETUPS: Vec<Once> =
+        (0..get_test_database_count()).map(|_| Once::new()).collect();
+}
+
+thread_local! {
+    /// The index of the database the test on this thread has leased, if any
+    static TEST_DATABASE: Cell<Option<usize>> = Cell::new(None);
+}
+
+/// One of the `PGX_TEST_DATABASES` databases, leased to the test running on this thread until
+/// dropped
+///
+/// libtest runs each test on a thread of its own, so rather than by thread, tests are assigned
+/// the lowest-numbered database that no other test is running in, and wait for one if all are
+/// busy.  That makes each database a worker that runs one test at a time.  When there's just the
+/// one database, every test runs in it at the same time, as it always has.
+struct TestDatabaseLease {
+    index: usize,
+}
+
+impl TestDatabaseLease {
+    fn acquire() -> Self {
+        let (busy, available) = &*BUSY_TEST_DATABASES;
+        let mut busy = busy.lock().unwrap_or_else(|e| e.into_inner());
+        let index = loop {
+            match busy.iter().position(|busy| !busy) {
+                Some(index) => break index,
+                None => busy = available.wait(busy).unwrap_or_else(|e| e.into_inner()),
+            }
+        };
+        if busy.len() > 1 {
+            busy[index] = true;
+        }
+        drop(busy);
+
+        TEST_DATABASE.with(|current| current.set(Some(index)));
+        TestDatabaseLease { index }
+    }
+}
+
+impl Drop for TestDatabaseLease {
+    fn drop(&mut self) {
+        TEST_DATABASE.with(|current| current.set(None));
+        let (busy, available) = &*BUSY_TEST_DATABASES;
+        busy.lock().unwrap_or_else(|e| e.into_inner())[self.index] = false;
+        available.notify_one();
+    }
 }
 
 fn register_shutdown_hook() {
@@ -49,13 +107,165 @@ where
     SHUTDOWN_HOOKS.lock().unwrap().push(Box::new(func));
 }
 
-pub fn run_test(
+/// Run `query` through SPI and assert that its first column of its first row equals `expected`
+///
+/// The first cell is converted to the type of `expected`.  On failure, the panic message includes
+/// the query text along with both values, and the query's complete result, every row and
+/// column of it, as formatted by [`format_spi_result()`].
+///
+/// ```rust,ignore
+/// #[pg_test]
+/// fn test_answer() {
+///     pgx_tests::assert_spi_eq!(42, "SELECT 42");
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_spi_eq {
+    ($expected:expr, $query:expr $(,)?) => {{
+        let query: &str = $query;
+        let expected = Some($expected);
+        let actual = ::pgx::Spi::get_one(query);
+        if expected != actual {
+            panic!(
+                "assert_spi_eq!() failed for query `{}`: expected `{:?}`, got `{:?}`\n{}",
+                query,
+                expected,
+                actual,
+                $crate::format_spi_result(query)
+            );
+        }
+    }};
+}
+
+/// Run `query` through SPI and format its complete result as a table, much like `psql` would
+///
+/// NULLs are shown as `NULL`.  Used by [`assert_spi_eq!()`] to explain a failure, for which the
+/// query is run a second time.
+pub fn format_spi_result(query: &str) -> String {
+    let mut names = Vec::new();
+    let mut rows = Vec::new();
+    Spi::connect(|client| {
+        let table = client.select(query, None, None);
+        names = (1..=table.column_count() as i32)
+            .map(|ordinal| table.column_name(ordinal).unwrap_or_default())
+            .collect::<Vec<_>>();
+        rows = table
+            .get_text_rows()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| value.unwrap_or_else(|| String::from("NULL")))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(()))
+    });
+
+    let mut widths = names.iter().map(|name| name.len()).collect::<Vec<_>>();
+    for row in &rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.len());
+        }
+    }
+    let format_row = |values: &[String]| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!(" {:width$} ", value, width = width))
+            .collect::<Vec<_>>()
+            .join("|")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut result = format_row(&names);
+    result.push('\n');
+    result.push_str(
+        &widths
+            .iter()
+            .map(|width| "-".repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join("+"),
+    );
+    result.push('\n');
+    for row in &rows {
+        result.push_str(&format_row(row));
+        result.push('\n');
+    }
+    result.push_str(&match rows.len() {
+        1 => String::from("(1 row)"),
+        n => format!("({} rows)", n),
+    });
+    result
+}
+
+/// Run the `#[pg_test]` function `sql_funcname`, concurrently with other tests
+///
+/// Tests are spread across `PGX_TEST_DATABASES` databases (1, by default).  Each is a copy of a
+/// template database the extension was created in once, so any SQL that creating the extension
+/// runs is shared setup for all of them.  They're dropped when the test framework exits.
+///
+/// `setup` is the crate's `pg_test::setup()`, with this test's options, and runs once the test has
+/// been given its database.
+pub fn run_test<F: FnOnce()>(
     sql_funcname: &str,
     expected_error: Option<&str>,
     postgresql_conf: Vec<&'static str>,
+    setup: F,
 ) {
     let (loglines, system_session_id) = initialize_test_framework(postgresql_conf);
+    let _guard = SERIAL_LOCK.read().unwrap_or_else(|e| e.into_inner());
+    let _lease = TestDatabaseLease::acquire();
+    setup();
+    execute_test(sql_funcname, expected_error, loglines, system_session_id)
+}
 
+/// Run the `#[pg_test(serial)]` function `sql_funcname`, while no other test is running
+pub fn run_serial_test<F: FnOnce()>(
+    sql_funcname: &str,
+    expected_error: Option<&str>,
+    postgresql_conf: Vec<&'static str>,
+    setup: F,
+) {
+    let (loglines, system_session_id) = initialize_test_framework(postgresql_conf);
+    let _guard = SERIAL_LOCK.write().unwrap_or_else(|e| e.into_inner());
+    let _lease = TestDatabaseLease::acquire();
+    setup();
+    execute_test(sql_funcname, expected_error, loglines, system_session_id)
+}
+
+/// Run `setup` once in each of the `PGX_TEST_DATABASES` databases, before the first test that runs
+/// in it
+///
+/// Your crate's `pg_test::setup()` runs before every test, with that test's options.  Setup that
+/// should only happen once per database, such as loading fixtures through [`client()`], can be
+/// wrapped in this instead:
+///
+/// ```rust,ignore
+/// pub fn setup(_options: Vec<&str>) {
+///     pgx_tests::setup_test_database_once(|| {
+///         let (mut client, _) = pgx_tests::client();
+///         client.batch_execute("CREATE TABLE fixtures (id int)").unwrap();
+///     });
+/// }
+/// ```
+///
+/// # Panics
+///
+/// If it's called outside of `pg_test::setup()` or a test.
+pub fn setup_test_database_once<F: FnOnce()>(setup: F) {
+    let index = TEST_DATABASE
+        .with(Cell::get)
+        .expect("setup_test_database_once() must be called from pg_test::setup()");
+    TEST_DATABASE_SETUPS[index].call_once(setup);
+}
+
+fn execute_test(
+    sql_funcname: &str,
+    expected_error: Option<&str>,
+    loglines: LogLines,
+    system_session_id: String,
+) {
     let (mut client, session_id) = client();
 
     let schema = "tests"; // get_extension_schema();
@@ -168,9 +378,13 @@ fn initialize_test_framework(postgresql_conf: Vec<&'static str>) -> (LogLines, S
 
         let system_session_id = start_pg(state.loglines.clone());
         let pg_config = get_pg_config();
-        dropdb();
+        for i in 0..get_test_database_count() {
+            dropdb(&get_test_database_name(i));
+        }
+        dropdb(get_pg_dbname());
         createdb(&pg_config, get_pg_dbname(), true, false).expect("failed to create test database");
         create_extension();
+        create_test_databases();
 
         state.installed = true;
         state.system_session_id = system_session_id;
@@ -186,7 +400,27 @@ fn get_pg_config() -> PgConfig {
         .clone()
 }
 
+/// Connect to the database the calling test runs in, first starting Postgres with
+/// `postgresql_conf` if no test has yet
+///
+/// This is for the few tests which can't be a `#[pg_test]`, as they must run a statement outside
+/// of a transaction, such as the `CALL` of a procedure that commits.  As they don't lease a
+/// database of their own, they run in the template database.
+pub fn test_client(postgresql_conf: Vec<&'static str>) -> (postgres::Client, String) {
+    let _ = initialize_test_framework(postgresql_conf);
+    client()
+}
+
+/// Connect to the database the calling test runs in: the one it has leased, if it's a
+/// `#[pg_test]`, or the template database otherwise
 pub fn client() -> (postgres::Client, String) {
+    match TEST_DATABASE.with(Cell::get) {
+        Some(index) if get_test_database_count() > 1 => connect(&get_test_database_name(index)),
+        _ => connect(get_pg_dbname()),
+    }
+}
+
+fn connect(dbname: &str) -> (postgres::Client, String) {
     fn determine_session_id(client: &mut Client) -> String {
         let result = client.query("SELECT to_hex(trunc(EXTRACT(EPOCH FROM backend_start))::integer) || '.' || to_hex(pid) AS sid FROM pg_stat_activity WHERE pid = pg_backend_pid();", &[]).expect("failed to determine session id");
 
@@ -205,7 +439,7 @@ pub fn client() -> (postgres::Client, String) {
                 .expect("unable to determine test port"),
         )
         .user(&get_pg_user())
-        .dbname(&get_pg_dbname())
+        .dbname(dbname)
         .connect(postgres::NoTls)
         .unwrap();
 
@@ -345,6 +579,8 @@ fn start_pg(loglines: LogLines) -> String {
 
     // add a shutdown hook so we can terminate it when the test framework exits
     add_shutdown_hook(move || unsafe {
+        drop_test_databases();
+
         let message_string =
             std::ffi::CString::new("Stopping Postgres\n\n".bold().blue().to_string()).unwrap();
         libc::printf(message_string.as_ptr());
@@ -435,7 +671,7 @@ fn monitor_pg(mut command: Command, cmd_string: String, loglines: LogLines) -> (
     receiver.recv().expect("Postgres failed to start")
 }
 
-fn dropdb() {
+fn dropdb(dbname: &str) {
     let pg_config = get_pg_config();
     let output = Command::new(
         pg_config
@@ -456,17 +692,14 @@ fn dropdb() {
             .expect("unable to determine test port")
             .to_string(),
     )
-    .arg(get_pg_dbname())
+    .arg(dbname)
     .output()
     .unwrap();
 
     if !output.status.success() {
         // maybe the database didn't exist, and if so that's okay
         let stderr = String::from_utf8_lossy(output.stderr.as_slice());
-        if !stderr.contains(&format!(
-            "ERROR:  database \"{}\" does not exist",
-            get_pg_dbname()
-        )) {
+        if !stderr.contains(&format!("ERROR:  database \"{}\" does not exist", dbname)) {
             // got some error we didn't expect
             eprintln!("{}", String::from_utf8_lossy(output.stdout.as_slice()));
             eprintln!("{}", stderr);
@@ -476,7 +709,7 @@ fn dropdb() {
 }
 
 fn create_extension() {
-    let (mut client, _) = client();
+    let (mut client, _) = connect(get_pg_dbname());
 
     client
         .simple_query(&format!(
@@ -486,6 +719,56 @@ fn create_extension() {
         .unwrap();
 }
 
+fn create_test_databases() {
+    if get_test_database_count() == 1 {
+        return;
+    }
+
+    // the template can't be connected to while it's being copied
+    let (mut client, _) = connect("postgres");
+    for i in 0..get_test_database_count() {
+        client
+            .simple_query(&format!(
+                "CREATE DATABASE \"{}\" TEMPLATE \"{}\";",
+                get_test_database_name(i),
+                get_pg_dbname()
+            ))
+            .expect("failed to create test database from template");
+    }
+}
+
+fn drop_test_databases() {
+    if get_test_database_count() == 1 {
+        return;
+    }
+
+    let (mut client, _) = connect("postgres");
+    for i in 0..get_test_database_count() {
+        let dbname = get_test_database_name(i);
+        if let Err(e) = client.simple_query(&format!("DROP DATABASE IF EXISTS \"{}\";", dbname)) {
+            // they're dropped again before the next run, so this isn't fatal
+            eprintln!("failed to drop test database {}: {}", dbname, e);
+        }
+    }
+}
+
+fn get_test_database_count() -> usize {
+    match std::env::var("PGX_TEST_DATABASES") {
+        Ok(count) => match count.parse::<usize>() {
+            Ok(count) if count > 0 => count,
+            _ => panic!(
+                "PGX_TEST_DATABASES must be a positive integer, got `{}`",
+                count
+            ),
+        },
+        Err(_) => 1,
+    }
+}
+
+fn get_test_database_name(i: usize) -> String {
+    format!("{}_{}", get_pg_dbname(), i)
+}
+
 fn get_extension_name() -> String {
     std::env::var("CARGO_PKG_NAME")
         .unwrap_or_else(|_| panic!("CARGO_PKG_NAME is not an envvar"))
@@ -17,6 +17,6 @@ pub mod pg_test {
     }
 
     pub fn postgresql_conf_options() -> Vec<&'static str> {
-        vec![]
+        vec!["pgx_tests.conf_int = 250"]
     }
 }
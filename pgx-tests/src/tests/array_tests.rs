@@ -36,6 +36,40 @@ fn sum_array_i64_sliced(values: Array<i64>) -> i64 {
     values.as_slice().iter().sum()
 }
 
+/// Sums a large `int4[]` in place, accumulating into an `i64` so that it doesn't overflow
+#[pg_extern]
+fn sum_large_array_sliced(values: Array<i32>) -> i64 {
+    values.as_slice().iter().map(|v| *v as i64).sum()
+}
+
+/// Sums an `int4[]` in place if it has no NULLs, or element by element (skipping NULLs) if it does
+#[pg_extern]
+fn sum_array_sliced_or_iterated(values: Array<i32>) -> i64 {
+    match values.try_as_slice() {
+        Some(slice) => slice.iter().map(|v| *v as i64).sum(),
+        None => values.iter().flatten().map(|v| v as i64).sum(),
+    }
+}
+
+/// Is the memory of `values.as_slice()` inside the array's own datum, rather than a copy of it?
+#[pg_extern]
+fn array_slice_is_borrowed(values: Array<i32>) -> bool {
+    let slice = values.as_slice();
+    let start = slice.as_ptr() as usize;
+    let end = start + std::mem::size_of_val(slice);
+    let array = values.into_array_type();
+    let array_start = array as usize;
+    let array_end = array_start + unsafe { varsize(array as *const pg_sys::varlena) };
+    array_start < start && end <= array_end
+}
+
+/// The number of NULLs in a `text[]`, which can't be borrowed through `as_slice()`
+#[pg_extern]
+fn count_nulls_datum_slice(values: Array<&str>) -> i32 {
+    let (_, nulls) = values.as_datum_slice();
+    nulls.iter().filter(|is_null| **is_null).count() as i32
+}
+
 #[pg_extern]
 fn count_true(values: Array<bool>) -> i32 {
     values.iter().filter(|b| b.unwrap_or(false)).count() as i32
@@ -130,6 +164,46 @@ mod tests {
         assert_eq!(sum.unwrap(), 6);
     }
 
+    #[pg_test]
+    fn test_sum_large_array_sliced() {
+        let sum = Spi::get_one::<i64>(
+            "SELECT sum_large_array_sliced(array_agg(s)) FROM generate_series(1, 1000000) s",
+        );
+        assert_eq!(sum, Some(500_000_500_000));
+    }
+
+    #[pg_test]
+    fn test_array_slice_is_borrowed() {
+        let borrowed = Spi::get_one::<bool>(
+            "SELECT array_slice_is_borrowed(array_agg(s)) FROM generate_series(1, 1000) s",
+        );
+        assert_eq!(borrowed, Some(true));
+    }
+
+    #[pg_test]
+    fn test_sum_array_sliced_or_iterated() {
+        let sum = Spi::get_one::<i64>("SELECT sum_array_sliced_or_iterated(ARRAY[1,2,3])");
+        assert_eq!(sum, Some(6));
+        let sum = Spi::get_one::<i64>("SELECT sum_array_sliced_or_iterated(ARRAY[1,NULL,3])");
+        assert_eq!(sum, Some(4));
+        let sum = Spi::get_one::<i64>("SELECT sum_array_sliced_or_iterated('{{1,2},{3,4}}')");
+        assert_eq!(sum, Some(10));
+    }
+
+    #[pg_test(error = "array contains NULL")]
+    fn test_sum_array_sliced_with_null() {
+        Spi::get_one::<i32>("SELECT sum_array_siced(ARRAY[1,NULL,3]::integer[])");
+    }
+
+    #[pg_test]
+    fn test_count_nulls_datum_slice() {
+        let cnt =
+            Spi::get_one::<i32>("SELECT count_nulls_datum_slice(ARRAY['a', NULL, 'c', NULL])");
+        assert_eq!(cnt, Some(2));
+        let cnt = Spi::get_one::<i32>("SELECT count_nulls_datum_slice(ARRAY[]::text[])");
+        assert_eq!(cnt, Some(0));
+    }
+
     #[pg_test(error = "attempt to add with overflow")]
     fn test_sum_array_i32_overflow() {
         Spi::get_one::<i64>(
@@ -0,0 +1,51 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::bgworkers::*;
+use pgx::*;
+
+extension_sql!(
+    "CREATE TABLE bgworker_test (v int);",
+    name = "create_bgworker_test_table"
+);
+
+#[pg_guard]
+#[no_mangle]
+pub extern "C" fn bgworker_test_main(arg: pg_sys::Datum) {
+    let arg = unsafe { i32::from_datum(arg, false, pg_sys::INT4OID) }.expect("argument was NULL");
+
+    BackgroundWorker::attach_signal_handlers(SignalWakeFlags::SIGHUP | SignalWakeFlags::SIGTERM);
+    BackgroundWorker::connect_worker_to_spi(Some("pgx_tests"), None);
+    BackgroundWorker::transaction(|| {
+        Spi::run(&format!("INSERT INTO bgworker_test VALUES ({})", arg));
+    });
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::bgworkers::*;
+    use pgx::*;
+
+    #[pg_test]
+    fn test_dynamic_bgworker() {
+        let worker = BackgroundWorkerBuilder::new("dynamic_bgworker")
+            .set_library("pgx_tests")
+            .set_function("bgworker_test_main")
+            .set_argument(123i32.into_datum())
+            .enable_spi_access()
+            .set_notify_pid(unsafe { pg_sys::MyProcPid })
+            .load_dynamic()
+            .expect("failed to start the dynamic background worker");
+
+        assert!(worker.wait_for_startup().is_ok());
+        assert_eq!(Ok(()), worker.wait_for_shutdown());
+        assert_eq!(
+            Some(123),
+            Spi::get_one::<i32>("SELECT v FROM bgworker_test")
+        );
+    }
+}
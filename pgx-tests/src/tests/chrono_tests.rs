@@ -0,0 +1,99 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::chrono::{DateTime, NaiveDateTime, Utc};
+use pgx::*;
+
+#[pg_extern]
+fn accept_chrono_timestamp(t: NaiveDateTime) -> NaiveDateTime {
+    t
+}
+
+#[pg_extern]
+fn accept_chrono_timestamptz(t: DateTime<Utc>) -> DateTime<Utc> {
+    t
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+    use pgx::*;
+
+    fn naive(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32, us: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, mo, d)
+            .and_then(|date| date.and_hms_micro_opt(h, mi, s, us))
+            .unwrap()
+    }
+
+    #[pg_test]
+    fn test_chrono_timestamp_at_postgres_epoch() {
+        let result = Spi::get_one::<NaiveDateTime>("SELECT '2000-01-01 00:00:00'::timestamp");
+        assert_eq!(result, Some(naive(2000, 1, 1, 0, 0, 0, 0)));
+    }
+
+    #[pg_test]
+    fn test_chrono_timestamp_before_postgres_epoch() {
+        let result =
+            Spi::get_one::<NaiveDateTime>("SELECT '1999-12-31 23:59:59.999999'::timestamp");
+        assert_eq!(result, Some(naive(1999, 12, 31, 23, 59, 59, 999_999)));
+
+        let result =
+            Spi::get_one::<NaiveDateTime>("SELECT '1969-07-20 20:17:40.123456'::timestamp");
+        assert_eq!(result, Some(naive(1969, 7, 20, 20, 17, 40, 123_456)));
+    }
+
+    #[pg_test]
+    fn test_chrono_timestamptz_is_utc() {
+        let result =
+            Spi::get_one::<DateTime<Utc>>("SELECT '2000-01-01 02:00:00.000001+02'::timestamptz");
+        assert_eq!(
+            result,
+            Some(Utc.from_utc_datetime(&naive(2000, 1, 1, 0, 0, 0, 1)))
+        );
+    }
+
+    #[pg_test(error = "infinite timestamps cannot be represented by chrono")]
+    fn test_chrono_timestamp_infinity() {
+        Spi::get_one::<NaiveDateTime>("SELECT 'infinity'::timestamp");
+    }
+
+    #[pg_test(error = "infinite timestamps cannot be represented by chrono")]
+    fn test_chrono_timestamptz_neg_infinity() {
+        Spi::get_one::<DateTime<Utc>>("SELECT '-infinity'::timestamptz");
+    }
+
+    #[pg_test(error = "timestamp out of range for chrono")]
+    fn test_chrono_timestamp_out_of_range() {
+        // Postgres supports timestamps up to 294276 AD, but chrono only to 262143 AD
+        Spi::get_one::<NaiveDateTime>("SELECT '294000-01-01 00:00:00'::timestamp");
+    }
+
+    #[pg_test(error = "timestamp out of range")]
+    fn test_chrono_timestamp_into_out_of_range() {
+        // chrono supports dates as far back as 262144 BC, but Postgres only to 4714 BC
+        let ancient = NaiveDate::from_ymd_opt(-5000, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .unwrap();
+        ancient.into_datum();
+    }
+
+    #[pg_test]
+    fn test_chrono_timestamp_round_trip() {
+        let result = Spi::get_one::<bool>(
+            "SELECT accept_chrono_timestamp(ts) = ts FROM (SELECT '1999-12-31 23:59:59.999999'::timestamp AS ts) t",
+        );
+        assert_eq!(result, Some(true));
+    }
+
+    #[pg_test]
+    fn test_chrono_timestamptz_round_trip() {
+        let result = Spi::get_one::<bool>(
+            "SELECT accept_chrono_timestamptz(ts) = ts FROM (SELECT '1969-07-20 20:17:40.123456-07'::timestamptz AS ts) t",
+        );
+        assert_eq!(result, Some(true));
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+extension_sql!(
+    r#"CREATE TYPE composite_pair AS (a integer, b text);"#,
+    name = "create_composite_pair"
+);
+
+#[pg_extern]
+fn make_composite_pair(a: i32, b: Option<&str>) -> PgHeapTuple {
+    let tupdesc = composite_type!("composite_pair");
+    unsafe {
+        PgHeapTuple::from_datums(
+            &tupdesc,
+            &[a.into_datum().unwrap(), b.into_datum().unwrap_or(0)],
+            &[false, b.is_none()],
+        )
+    }
+}
+
+#[pg_extern]
+fn make_tuple_pair() -> (i32, String) {
+    (1, "x".to_string())
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_return_composite() {
+        let (a, b) = Spi::get_two::<i32, &str>(
+            "SELECT a, b FROM make_composite_pair(42, 'pgx') AS t(a integer, b text)",
+        );
+        assert_eq!(Some(42), a);
+        assert_eq!(Some("pgx"), b);
+    }
+
+    #[pg_test]
+    fn test_return_composite_with_null() {
+        let (a, b) = Spi::get_two::<i32, &str>(
+            "SELECT a, b FROM make_composite_pair(7, NULL) AS t(a integer, b text)",
+        );
+        assert_eq!(Some(7), a);
+        assert_eq!(None, b);
+    }
+
+    #[pg_test]
+    fn test_composite_type_lookup() {
+        let tupdesc = composite_type!("composite_pair");
+        assert_eq!(2, tupdesc.len());
+        assert!(PgTupleDesc::for_composite_type("integer").is_none());
+        assert!(PgTupleDesc::for_composite_type("no_such_type").is_none());
+    }
+
+    #[pg_test]
+    fn test_return_tuple_as_columns() {
+        let (a, b) = Spi::get_two::<i32, String>("SELECT * FROM make_tuple_pair()");
+        assert_eq!(Some(1), a);
+        assert_eq!(Some("x".to_string()), b);
+    }
+
+    #[pg_test]
+    fn test_tuple_from_record() {
+        assert_eq!(
+            Some((Some(1), Some("x".to_string()))),
+            Spi::get_one::<(Option<i32>, Option<String>)>("SELECT ROW(1, 'x')")
+        );
+        assert_eq!(
+            Some((Some(42), Some("pgx"))),
+            Spi::get_one::<(Option<i32>, Option<&str>)>("SELECT ROW(42, 'pgx')::composite_pair")
+        );
+        assert_eq!(
+            Some((None, Some("y"), Some(true), Some(2.5f64), None)),
+            Spi::get_one::<(
+                Option<i32>,
+                Option<&str>,
+                Option<bool>,
+                Option<f64>,
+                Option<i64>
+            )>("SELECT ROW(NULL::integer, 'y', true, 2.5::float8, NULL::bigint)")
+        );
+    }
+
+    #[pg_test]
+    fn test_tuple_into_record() {
+        assert_eq!(
+            Some("(1,x)".to_string()),
+            Spi::get_one_with_args::<String>(
+                "SELECT $1::text",
+                vec![(
+                    PgBuiltInOids::RECORDOID.oid(),
+                    (Some(1), Some("x")).into_datum()
+                )],
+            )
+        );
+        assert_eq!(
+            Some("(7,,t)".to_string()),
+            Spi::get_one_with_args::<String>(
+                "SELECT $1::text",
+                vec![(
+                    PgBuiltInOids::RECORDOID.oid(),
+                    (Some(7i64), None::<&str>, Some(true)).into_datum()
+                )],
+            )
+        );
+    }
+
+    #[pg_test(error = "expected a record with 2 fields, but it has 3")]
+    fn test_tuple_from_record_wrong_length() {
+        Spi::get_one::<(Option<i32>, Option<i32>)>("SELECT ROW(1, 2, 3)");
+    }
+
+    #[pg_test(error = "wrong number of datums for the tuple descriptor")]
+    fn test_from_datums_wrong_length() {
+        let tupdesc = composite_type!("composite_pair");
+        unsafe { PgHeapTuple::from_datums(&tupdesc, &[0], &[true]) };
+    }
+}
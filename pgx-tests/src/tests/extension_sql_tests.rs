@@ -0,0 +1,48 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+extension_sql!(
+    r#"
+CREATE FUNCTION plpgsql_base_offset() RETURNS integer LANGUAGE plpgsql IMMUTABLE AS $$
+BEGIN
+    RETURN 40;
+END;
+$$;
+"#,
+    name = "create_plpgsql_base_offset",
+    creates = [Function(plpgsql_base_offset)]
+);
+
+/// Its `CREATE FUNCTION` calls the PL/pgSQL function for its default, so fails unless that's
+/// created first
+#[pg_extern(requires = [plpgsql_base_offset])]
+fn offset_by_plpgsql(value: i32, offset: default!(i32, "plpgsql_base_offset()")) -> i32 {
+    value + offset
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_requires_plpgsql_function() {
+        assert_eq!(Spi::get_one::<i32>("SELECT offset_by_plpgsql(2)"), Some(42));
+        assert_eq!(
+            Spi::get_one::<i32>("SELECT offset_by_plpgsql(2, 1)"),
+            Some(3)
+        );
+        assert_eq!(
+            Spi::get_one::<String>(
+                "SELECT l.lanname::text FROM pg_proc p JOIN pg_language l ON l.oid = p.prolang
+                  WHERE p.proname = 'plpgsql_base_offset'"
+            ),
+            Some(String::from("plpgsql"))
+        );
+    }
+}
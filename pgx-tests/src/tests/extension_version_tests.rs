@@ -0,0 +1,35 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+#[pg_extern]
+fn pgx_tests_version() -> String {
+    extension_version!().expect("pgx_tests isn't installed")
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_extension_version() {
+        let installed = Spi::get_one::<String>(
+            "SELECT extversion FROM pg_extension WHERE extname = 'pgx_tests'",
+        );
+        assert_eq!(
+            installed,
+            Spi::get_one::<String>("SELECT pgx_tests_version()")
+        );
+        assert_eq!(installed.as_deref(), Some("1.0"));
+    }
+
+    #[pg_test]
+    fn test_extension_version_not_installed() {
+        assert_eq!(None, get_extension_version("no_such_extension"));
+    }
+}
@@ -76,6 +76,15 @@ fn returns_none() -> Option<i32> {
     None
 }
 
+#[pg_extern]
+fn returns_nested_option(which: &str) -> Option<Option<i32>> {
+    match which {
+        "none" => None,
+        "some_none" => Some(None),
+        _ => Some(Some(5)),
+    }
+}
+
 #[pg_extern]
 fn takes_void(_void: ()) {
     // noop
@@ -96,6 +105,18 @@ fn same_name(same_name: &str) -> &str {
     same_name
 }
 
+#[pg_extern]
+fn collation_of(_s: &str, fcinfo: pg_sys::FunctionCallInfo) -> pg_sys::Oid {
+    unsafe { pg_get_collation(fcinfo) }
+}
+
+#[pg_extern]
+fn arg_nulls(_a: Option<i32>, _b: Option<&str>, fcinfo: pg_sys::FunctionCallInfo) -> Vec<bool> {
+    (0..unsafe { pg_nargs(fcinfo) })
+        .map(|num| pg_arg_is_null(fcinfo, num))
+        .collect()
+}
+
 #[cfg(any(test, feature = "pg_test"))]
 #[pgx::pg_schema]
 mod tests {
@@ -244,6 +265,24 @@ mod tests {
         assert!(result.is_none())
     }
 
+    #[pg_test]
+    fn test_returns_nested_option_none() {
+        let result = Spi::get_one::<i32>("SELECT returns_nested_option('none');");
+        assert_eq!(None, result)
+    }
+
+    #[pg_test]
+    fn test_returns_nested_option_some_none() {
+        let result = Spi::get_one::<i32>("SELECT returns_nested_option('some_none');");
+        assert_eq!(None, result)
+    }
+
+    #[pg_test]
+    fn test_returns_nested_option_some_some() {
+        let result = Spi::get_one::<i32>("SELECT returns_nested_option('some_some');");
+        assert_eq!(Some(5), result)
+    }
+
     #[pg_test]
     fn test_takes_void() {
         let result = Spi::get_one::<()>("SELECT takes_void(NULL::void);");
@@ -262,6 +301,36 @@ mod tests {
         assert_eq!((Some(42), Some("pgx".into())), result)
     }
 
+    #[pg_test]
+    fn test_collation_of_default() {
+        let expected =
+            Spi::get_one::<pg_sys::Oid>("SELECT oid FROM pg_collation WHERE collname = 'default'");
+        let collation = Spi::get_one::<pg_sys::Oid>("SELECT collation_of('a')");
+        assert!(expected.is_some());
+        assert_eq!(expected, collation);
+    }
+
+    #[pg_test]
+    fn test_collation_of_explicit_collate() {
+        let expected =
+            Spi::get_one::<pg_sys::Oid>("SELECT oid FROM pg_collation WHERE collname = 'C'");
+        let collation = Spi::get_one::<pg_sys::Oid>("SELECT collation_of('a' COLLATE \"C\")");
+        assert!(expected.is_some());
+        assert_eq!(expected, collation);
+    }
+
+    #[pg_test]
+    fn test_arg_nulls() {
+        assert_eq!(
+            Some(vec![true, false]),
+            Spi::get_one::<Vec<bool>>("SELECT arg_nulls(NULL, 'b')")
+        );
+        assert_eq!(
+            Some(vec![false, true]),
+            Spi::get_one::<Vec<bool>>("SELECT arg_nulls(0, NULL)")
+        );
+    }
+
     /// ensures that we can have a `#[pg_extern]` function with an argument that
     /// shares its name
     #[pg_test]
@@ -49,6 +49,29 @@ mod tests {
         assert_eq!(GUC.get(), 12);
     }
 
+    #[pg_test]
+    fn test_int_guc_from_postgresql_conf() {
+        // `postgresql_conf_options()` sets this before it's defined here
+        static GUC: GucSetting<i32> = GucSetting::new(100);
+        GucRegistry::define_int_guc(
+            "pgx_tests.conf_int",
+            "test int guc from postgresql.conf",
+            "test int guc from postgresql.conf",
+            &GUC,
+            1,
+            1000,
+            GucContext::Userset,
+        );
+        assert_eq!(GUC.get(), 250);
+        assert_eq!(
+            Some(String::from("250")),
+            Spi::get_one::<String>("SELECT current_setting('pgx_tests.conf_int')")
+        );
+
+        Spi::run("SET pgx_tests.conf_int = 100");
+        assert_eq!(GUC.get(), 100);
+    }
+
     #[pg_test]
     fn test_float_guc() {
         static GUC: GucSetting<f64> = GucSetting::new(42.42);
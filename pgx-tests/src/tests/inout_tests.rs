@@ -0,0 +1,73 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+#[pg_extern]
+fn swap(a: inout!(i32), b: inout!(i32)) -> (i32, i32) {
+    (b, a)
+}
+
+#[pg_extern]
+fn double_it(value: inout!(i64)) -> i64 {
+    value * 2
+}
+
+#[pg_extern]
+fn scale_and_label(
+    factor: i32,
+    value: inout!(i32),
+    label: inout!(Option<&str>),
+) -> (i32, Option<String>) {
+    (value * factor, label.map(|label| label.to_uppercase()))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_swap() {
+        assert_eq!(
+            (Some(2), Some(1)),
+            Spi::get_two::<i32, i32>("SELECT a, b FROM swap(1, 2)")
+        );
+    }
+
+    #[pg_test]
+    fn test_swap_as_record() {
+        assert_eq!(
+            Some("(4,3)".to_string()),
+            Spi::get_one::<String>("SELECT swap(3, 4)::text")
+        );
+    }
+
+    #[pg_test]
+    fn test_single_inout() {
+        assert_eq!(Some(42), Spi::get_one::<i64>("SELECT double_it(21)"));
+        assert_eq!(
+            Some("value bigint".to_string()),
+            Spi::get_one::<String>("SELECT pg_get_function_result('double_it'::regproc)")
+        );
+    }
+
+    #[pg_test]
+    fn test_inout_with_in_arguments() {
+        assert_eq!(
+            Some("(30,TEN)".to_string()),
+            Spi::get_one::<String>("SELECT scale_and_label(3, 10, 'ten')::text")
+        );
+        assert_eq!(
+            Some("(6,)".to_string()),
+            Spi::get_one::<String>("SELECT scale_and_label(3, 2, NULL)::text")
+        );
+        assert_eq!(
+            Some("factor integer, INOUT value integer, INOUT label text".to_string()),
+            Spi::get_one::<String>("SELECT pg_get_function_arguments('scale_and_label'::regproc)")
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+/// The transition function for `internal_sum(integer)`, whose state is a Rust `i64`
+#[pg_extern(immutable)]
+fn internal_sum_state(
+    state: Internal,
+    value: Option<i32>,
+    fcinfo: pg_sys::FunctionCallInfo,
+) -> Internal {
+    let value = value.unwrap_or(0) as i64;
+    match unsafe { state.get_mut::<i64>() } {
+        Some(sum) => {
+            *sum += value;
+            state
+        }
+        None => {
+            let aggcontext =
+                unsafe { pg_agg_context(fcinfo) }.expect("not called as part of an aggregate");
+            Internal::new_in(aggcontext, value)
+        }
+    }
+}
+
+#[pg_extern(immutable)]
+fn internal_sum_final(state: Internal) -> Option<i64> {
+    unsafe { state.get::<i64>() }.copied()
+}
+
+extension_sql!(
+    r#"
+CREATE AGGREGATE internal_sum(integer) (
+    SFUNC = internal_sum_state,
+    STYPE = internal,
+    FINALFUNC = internal_sum_final
+);
+"#,
+    name = "create_internal_sum",
+    requires = [internal_sum_state, internal_sum_final]
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_internal_state_aggregate() {
+        assert_eq!(
+            Some(55),
+            Spi::get_one::<i64>("SELECT internal_sum(x) FROM generate_series(1, 10) x")
+        );
+    }
+
+    #[pg_test]
+    fn test_internal_state_aggregate_per_group() {
+        assert_eq!(
+            Some(vec![Some(25), Some(30)]),
+            Spi::get_one::<Vec<Option<i64>>>(
+                "SELECT array_agg(sum ORDER BY parity) FROM (
+                    SELECT x % 2 AS parity, internal_sum(x) AS sum
+                    FROM generate_series(1, 10) x
+                    GROUP BY x % 2
+                ) sums"
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_internal_state_aggregate_no_rows() {
+        assert_eq!(
+            None,
+            Spi::get_one::<i64>("SELECT internal_sum(x) FROM generate_series(1, 0) x")
+        );
+    }
+
+    #[pg_test(error = "not called as part of an aggregate")]
+    fn test_internal_state_outside_aggregate() {
+        Spi::run("SELECT internal_sum_state(NULL, 1)");
+    }
+}
@@ -76,6 +76,74 @@ mod tests {
         )
     }
 
+    #[pg_extern]
+    fn checked_divide(a: i32, b: i32) -> i32 {
+        if b == 0 {
+            ereport!(
+                ERROR,
+                errcode = "22012",
+                message = "division by zero",
+                detail = format!("tried to divide {} by zero", a),
+                hint = "check the divisor first",
+            );
+        }
+        a / b
+    }
+
+    #[pg_test]
+    fn test_ereport_macro_fields() {
+        Spi::run(
+            "CREATE FUNCTION tests.capture_error() RETURNS text[] LANGUAGE plpgsql AS $$
+                DECLARE state text; message text; detail text; hint text;
+                BEGIN
+                    PERFORM tests.checked_divide(42, 0);
+                    RETURN NULL;
+                EXCEPTION WHEN others THEN
+                    GET STACKED DIAGNOSTICS state = RETURNED_SQLSTATE, message = MESSAGE_TEXT,
+                        detail = PG_EXCEPTION_DETAIL, hint = PG_EXCEPTION_HINT;
+                    RETURN ARRAY[state, message, detail, hint];
+                END
+            $$",
+        );
+        assert_eq!(
+            Some(vec![
+                "22012".to_string(),
+                "division by zero".to_string(),
+                "tried to divide 42 by zero".to_string(),
+                "check the divisor first".to_string(),
+            ]),
+            Spi::get_one::<Vec<String>>("SELECT tests.capture_error()")
+        );
+    }
+
+    #[pg_test]
+    fn test_ereport_macro_returns_below_error() {
+        ereport!(
+            WARNING,
+            errcode = PgSqlErrorCode::ERRCODE_WARNING_DEPRECATED_FEATURE,
+            message = "ereport warning",
+        );
+        assert_eq!(
+            Some(6),
+            Spi::get_one::<i32>("SELECT tests.checked_divide(42, 7)")
+        );
+    }
+
+    #[pg_test(error = "ereport macro error")]
+    fn test_ereport_macro_error() {
+        ereport!(ERROR, message = "ereport macro error")
+    }
+
+    #[pg_test]
+    fn test_elog_macro() {
+        elog!(NOTICE, "elog {} message", "notice");
+    }
+
+    #[pg_test(error = "elog error message")]
+    fn test_elog_macro_error() {
+        elog!(ERROR, "elog {} message", "error")
+    }
+
     #[pg_test(error = "panic message")]
     fn test_panic() {
         panic!("panic message")
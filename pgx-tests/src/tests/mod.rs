@@ -3,31 +3,47 @@
 
 mod anyarray_tests;
 mod array_tests;
+mod bgworker_tests;
 mod bytea_tests;
 mod cfg_tests;
+#[cfg(feature = "chrono")]
+mod chrono_tests;
+mod composite_type_tests;
 mod datetime_tests;
 mod default_arg_value_tests;
 mod derive_pgtype_lifetimes;
 mod enum_type_tests;
+mod extension_sql_tests;
+mod extension_version_tests;
 mod fcinfo_tests;
 mod guc_tests;
 mod hooks_tests;
 mod inet_tests;
+mod inout_tests;
+mod internal_tests;
 mod json_tests;
 mod lifetime_tests;
 mod log_tests;
 mod memcxt_tests;
 mod name_tests;
+mod native_datetime_tests;
 mod numeric_tests;
+mod operator_class_tests;
 mod pg_extern_args_tests;
+mod pg_test_cases_tests;
 mod pg_try_tests;
 mod postgres_type_tests;
+mod procedure_tests;
+mod rel_tests;
 mod schema_tests;
 mod spi_tests;
 mod srf_tests;
+mod stringinfo_tests;
 mod struct_type_tests;
 mod uuid_tests;
+mod varchar_tests;
 mod variadic_tests;
+mod window_tests;
 mod xact_callback_tests;
 mod xid64_tests;
 
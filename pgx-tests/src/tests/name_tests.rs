@@ -15,4 +15,14 @@ mod tests {
     fn renamed_func() {
         Spi::run("SELECT renamed_func();");
     }
+
+    #[pg_test]
+    fn renamed_func_links_to_wrapper_symbol() {
+        // `AS 'MODULE_PATHNAME', '<symbol>'` lands in `probin` and `prosrc` respectively
+        let (probin, prosrc) = Spi::get_two::<String, String>(
+            "SELECT probin, prosrc FROM pg_proc WHERE proname = 'renamed_func'",
+        );
+        assert!(probin.expect("probin was NULL").contains("pgx_tests"));
+        assert_eq!(prosrc, Some("func_to_rename_wrapper".to_string()));
+    }
 }
@@ -0,0 +1,171 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+#[pg_extern]
+fn accept_native_date(d: datetime::CivilDate) -> datetime::CivilDate {
+    d
+}
+
+#[pg_extern]
+fn accept_native_time(t: datetime::CivilTime) -> datetime::CivilTime {
+    t
+}
+
+#[pg_extern]
+fn accept_native_timestamp(t: datetime::CivilTimestamp) -> datetime::CivilTimestamp {
+    t
+}
+
+#[pg_extern]
+fn accept_native_interval(i: datetime::Interval) -> datetime::Interval {
+    i
+}
+
+#[pg_extern]
+fn native_first_of_month(d: datetime::CivilDate) -> datetime::CivilDate {
+    datetime::CivilDate::new(d.year(), d.month(), 1).expect("invalid date")
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::datetime::{CivilDate, CivilTime, CivilTimestamp, Interval};
+    use pgx::*;
+
+    /// `value`, passed to Postgres as an argument and back out, and as `text`
+    fn round_trip<T: FromDatum + IntoDatum + Copy>(
+        value: T,
+        function: &str,
+    ) -> (Option<T>, String) {
+        let oid = PgOid::from(T::type_oid());
+        let returned = Spi::get_one_with_args::<T>(
+            &format!("SELECT {}($1)", function),
+            vec![(oid, value.into_datum())],
+        );
+        let text = Spi::get_one_with_args::<String>(
+            &format!("SELECT {}($1)::text", function),
+            vec![(oid, returned.into_datum())],
+        )
+        .expect("text was NULL");
+        (returned, text)
+    }
+
+    #[pg_test]
+    fn test_native_date_round_trip() {
+        let date = CivilDate::new(2021, 3, 4).unwrap();
+        assert_eq!(
+            round_trip(date, "accept_native_date"),
+            (Some(date), String::from("2021-03-04"))
+        );
+        assert_eq!(
+            round_trip(CivilDate::INFINITY, "accept_native_date").1,
+            "infinity"
+        );
+
+        let date = Spi::get_one::<CivilDate>("SELECT '0044-03-15 BC'::date").unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (-44, 3, 15));
+        assert_eq!(
+            Spi::get_one::<CivilDate>("SELECT native_first_of_month('2021-03-04')"),
+            CivilDate::new(2021, 3, 1)
+        );
+    }
+
+    #[pg_test]
+    fn test_native_date_accessors_match_extract() {
+        Spi::connect(|client| {
+            let rows = client.select(
+                "SELECT d::date,
+                        EXTRACT(YEAR FROM d)::integer, EXTRACT(MONTH FROM d)::integer,
+                        EXTRACT(DAY FROM d)::integer, EXTRACT(DOW FROM d)::integer,
+                        EXTRACT(DOY FROM d)::integer
+                   FROM generate_series('1000-01-01 BC'::timestamp, '3000-12-31', '7919 hours') d",
+                None,
+                None,
+            );
+            assert!(rows.len() > 1000);
+            for row in rows {
+                let date = row.by_ordinal(1).unwrap().value::<CivilDate>().unwrap();
+                let extracted = (2..=6)
+                    .map(|ordinal| row.by_ordinal(ordinal).unwrap().value::<i32>().unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    vec![
+                        date.year(),
+                        date.month() as i32,
+                        date.day() as i32,
+                        date.day_of_week() as i32,
+                        date.day_of_year() as i32
+                    ],
+                    extracted
+                );
+            }
+            Ok(Some(()))
+        });
+    }
+
+    #[pg_test]
+    fn test_native_time_round_trip() {
+        let time = CivilTime::new(12, 34, 56, 789).unwrap();
+        assert_eq!(
+            round_trip(time, "accept_native_time"),
+            (Some(time), String::from("12:34:56.000789"))
+        );
+
+        let time = Spi::get_one::<CivilTime>("SELECT '23:59:58.5'::time").unwrap();
+        assert_eq!(
+            (
+                time.hour(),
+                time.minute(),
+                time.second(),
+                time.microsecond()
+            ),
+            (23, 59, 58, 500_000)
+        );
+    }
+
+    #[pg_test]
+    fn test_native_timestamp_round_trip() {
+        let timestamp = CivilTimestamp::new(
+            CivilDate::new(1999, 12, 31).unwrap(),
+            CivilTime::new(23, 0, 1, 0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            round_trip(timestamp, "accept_native_timestamp"),
+            (Some(timestamp), String::from("1999-12-31 23:00:01"))
+        );
+        assert_eq!(
+            round_trip(CivilTimestamp::NEG_INFINITY, "accept_native_timestamp").1,
+            "-infinity"
+        );
+
+        let timestamp =
+            Spi::get_one::<CivilTimestamp>("SELECT '2021-03-04 05:06:07.08'::timestamp").unwrap();
+        assert_eq!(timestamp.date(), CivilDate::new(2021, 3, 4).unwrap());
+        assert_eq!(timestamp.time(), CivilTime::new(5, 6, 7, 80_000).unwrap());
+    }
+
+    #[pg_test]
+    fn test_native_interval_round_trip() {
+        let interval = Interval::new(14, 3, 4 * 3_600_000_000 + 5_000_000);
+        assert_eq!(
+            round_trip(interval, "accept_native_interval"),
+            (
+                Some(interval),
+                String::from("1 year 2 mons 3 days 04:00:05")
+            )
+        );
+
+        let interval =
+            Spi::get_one::<Interval>("SELECT '-1 mons 2 days 00:00:00.5'::interval").unwrap();
+        assert_eq!(
+            (interval.months(), interval.days(), interval.micros()),
+            (-1, 2, 500_000)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+/// A string that compares, and hashes, without regard to case
+#[derive(Debug, Serialize, Deserialize, PostgresType, PostgresEq, PostgresOrd, PostgresHash)]
+pub struct CaseInsensitive(String);
+
+impl PartialEq for CaseInsensitive {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_lowercase() == other.0.to_lowercase()
+    }
+}
+
+impl Eq for CaseInsensitive {}
+
+impl PartialOrd for CaseInsensitive {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CaseInsensitive {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.to_lowercase().cmp(&other.0.to_lowercase())
+    }
+}
+
+impl Hash for CaseInsensitive {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_lowercase().hash(state)
+    }
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use crate::tests::operator_class_tests::CaseInsensitive;
+    use pgx::*;
+
+    #[pg_test]
+    fn test_btree_index_on_custom_type() {
+        Spi::run("CREATE TABLE tests.ci (v CaseInsensitive)");
+        Spi::run("CREATE INDEX ci_btree ON tests.ci USING btree (v)");
+        Spi::run(r#"INSERT INTO tests.ci VALUES ('"B"'), ('"a"'), ('"C"')"#);
+
+        let ordered = Spi::get_column::<CaseInsensitive>("SELECT v FROM tests.ci ORDER BY v")
+            .into_iter()
+            .map(|v| v.expect("got a NULL value").0)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["a", "B", "C"], ordered);
+    }
+
+    #[pg_test(error = "duplicate key value violates unique constraint \"ci_unique\"")]
+    fn test_unique_index_on_custom_type() {
+        Spi::run("CREATE TABLE tests.ci (v CaseInsensitive)");
+        Spi::run("CREATE UNIQUE INDEX ci_unique ON tests.ci USING btree (v)");
+        Spi::run(r#"INSERT INTO tests.ci VALUES ('"pgx"')"#);
+        Spi::run(r#"INSERT INTO tests.ci VALUES ('"PGX"')"#);
+    }
+
+    #[pg_test]
+    fn test_hash_index_on_custom_type() {
+        Spi::run("CREATE TABLE tests.ci (v CaseInsensitive)");
+        Spi::run("CREATE INDEX ci_hash ON tests.ci USING hash (v)");
+        Spi::run(r#"INSERT INTO tests.ci VALUES ('"pgx"'), ('"PGX"'), ('"Rust"')"#);
+
+        assert_eq!(
+            Some(2),
+            Spi::get_one::<i64>("SELECT count(DISTINCT v) FROM tests.ci")
+        );
+
+        // make sure the lookup goes through the index
+        Spi::run("SET LOCAL enable_seqscan TO off");
+        assert_eq!(
+            Some(2),
+            Spi::get_one::<i64>(r#"SELECT count(*) FROM tests.ci WHERE v = '"Pgx"'"#)
+        );
+    }
+}
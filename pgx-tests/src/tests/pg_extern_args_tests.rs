@@ -20,4 +20,62 @@ mod tests {
         .expect("failed to get SPI result");
         assert!(result)
     }
+
+    #[pg_extern]
+    fn planner_support_noop(_request: Internal) -> Internal {
+        // returning NULL tells the planner we have nothing to offer for this request
+        Internal::from(None)
+    }
+
+    #[pg_extern(immutable, support = planner_support_noop)]
+    fn has_planner_support(i: i32) -> i32 {
+        i
+    }
+
+    #[pg_test]
+    fn test_support() {
+        let result = Spi::get_one::<bool>(
+            "SELECT prosupport = 'tests.planner_support_noop'::regproc FROM pg_proc WHERE proname = 'has_planner_support'",
+        )
+        .expect("failed to get SPI result");
+        assert!(result);
+        assert_eq!(
+            Some(42),
+            Spi::get_one::<i32>("SELECT tests.has_planner_support(42)")
+        );
+    }
+
+    #[pg_test]
+    fn test_support_sql() {
+        use pgx::datum::sql_entity_graph::{PgxSql, SqlGraphEntity};
+
+        let entities = vec![
+            SqlGraphEntity::ExtensionRoot(crate::__pgx_marker().unwrap()),
+            __pgx_internals_fn_has_planner_support(),
+            __pgx_internals_fn_planner_support_noop(),
+        ];
+        let sql = PgxSql::build(
+            DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(),
+            DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(),
+            entities.into_iter(),
+        )
+        .expect("failed to build the SQL entity graph")
+        .to_sql()
+        .expect("failed to generate SQL");
+
+        assert!(
+            sql.contains("SUPPORT \"planner_support_noop\"\n"),
+            "{}",
+            sql
+        );
+
+        // the support function has to exist before the function referencing it
+        let support_fn = sql
+            .find("FUNCTION \"planner_support_noop\"")
+            .expect("no CREATE FUNCTION for planner_support_noop");
+        let supported_fn = sql
+            .find("FUNCTION \"has_planner_support\"")
+            .expect("no CREATE FUNCTION for has_planner_support");
+        assert!(support_fn < supported_fn, "{}", sql);
+    }
 }
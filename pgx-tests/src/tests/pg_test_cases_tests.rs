@@ -0,0 +1,41 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test(cases = [(vec![1, 2, 3], 2), (vec![10, 20], 15), (vec![-4, 4, 6], 2)])]
+    fn test_avg_cases(values: Vec<i32>, expected: i32) {
+        assert_eq!(
+            Some(expected),
+            Spi::get_one_with_args::<i32>(
+                "SELECT avg(x)::integer FROM unnest($1) x",
+                vec![(PgBuiltInOids::INT4ARRAYOID.oid(), values.into_datum())],
+            )
+        );
+    }
+
+    #[pg_test(cases = ["pgx", ""])]
+    fn test_single_argument_cases(value: &str) {
+        assert_eq!(
+            Some(value.len() as i32),
+            Spi::get_one_with_args::<i32>(
+                "SELECT octet_length($1)",
+                vec![(PgBuiltInOids::TEXTOID.oid(), value.into_datum())],
+            )
+        );
+    }
+
+    #[pg_test(error = "division by zero", cases = [0, i32::default()])]
+    fn test_error_cases(divisor: i32) {
+        Spi::get_one_with_args::<i32>(
+            "SELECT 1 / $1",
+            vec![(PgBuiltInOids::INT4OID.oid(), divisor.into_datum())],
+        );
+    }
+}
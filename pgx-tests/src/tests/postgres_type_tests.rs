@@ -53,6 +53,11 @@ impl InOutFuncs for CustomTextFormatSerializedType {
     }
 }
 
+#[pg_extern]
+fn varlena_type_identity(input: PgVarlena<VarlenaType>) -> PgVarlena<VarlenaType> {
+    input
+}
+
 #[derive(Serialize, Deserialize, PostgresType)]
 pub struct JsonType {
     a: f32,
@@ -60,6 +65,46 @@ pub struct JsonType {
     c: i64,
 }
 
+#[derive(Serialize, Deserialize, PostgresType)]
+#[pg_type(storage = "external", alignment = "double")]
+pub struct ExternalStorageType {
+    a: f32,
+    b: f32,
+    c: i64,
+}
+
+/// A fixed-length, 32 byte type, written as 64 hex digits
+#[derive(Copy, Clone, Debug, PartialEq, PostgresType)]
+#[pg_type(internallength = 32, alignment = "char")]
+#[inoutfuncs]
+pub struct Sha256([u8; 32]);
+
+impl InOutFuncs for Sha256 {
+    fn input(input: &CStr) -> Self {
+        let hex = input.to_str().expect("input is not valid UTF-8");
+        if hex.len() != 64 || !hex.is_ascii() {
+            panic!("a Sha256 must be 64 hex digits");
+        }
+
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).expect("invalid hex digit");
+        }
+        Sha256(bytes)
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        for byte in &self.0 {
+            buffer.push_str(&format!("{:02x}", byte));
+        }
+    }
+}
+
+#[pg_extern]
+fn sha256_identity(input: Sha256) -> Sha256 {
+    input
+}
+
 #[cfg(any(test, feature = "pg_test"))]
 #[pgx::pg_schema]
 mod tests {
@@ -67,8 +112,9 @@ mod tests {
     use crate as pgx_tests;
 
     use crate::tests::postgres_type_tests::{
-        CustomTextFormatSerializedType, JsonType, VarlenaType,
+        CustomTextFormatSerializedType, JsonType, Sha256, VarlenaType,
     };
+    use pgx::datum::sql_entity_graph::{PgxSql, SqlGraphEntity};
     use pgx::*;
 
     #[pg_test]
@@ -80,6 +126,70 @@ mod tests {
         assert_eq!(result.c, 3);
     }
 
+    #[pg_test]
+    fn test_mytype_output() {
+        let mut value = PgVarlena::<VarlenaType>::new();
+        value.a = 6.0;
+        value.b = 3.0;
+        value.c = 2;
+
+        let mut buffer = StringInfo::new();
+        value.output(&mut buffer);
+        assert_eq!(buffer.as_str(), Ok("6,3,2"));
+    }
+
+    #[pg_test]
+    fn test_pgvarlena_modify() {
+        let original = Spi::get_one::<PgVarlena<VarlenaType>>("SELECT '1.0,2.0,3'::VarlenaType")
+            .expect("SPI returned NULL");
+        let modified = original.modify(|v| {
+            v.a += 1.0;
+            v.c *= 2;
+        });
+
+        assert_eq!(original.a, 1.0);
+        assert_eq!(original.c, 3);
+        assert_eq!(modified.a, 2.0);
+        assert_eq!(modified.b, 2.0);
+        assert_eq!(modified.c, 6);
+
+        let inner: VarlenaType = modified.into_inner();
+        assert_eq!(inner.c, 6);
+    }
+
+    #[pg_test]
+    fn test_type_sql_precedes_functions_using_it() {
+        use crate::tests::postgres_type_tests::{
+            __pgx_internals_fn_varlena_type_identity, __pgx_internals_fn_varlenatype_in,
+            __pgx_internals_fn_varlenatype_out, __pgx_internals_type_VarlenaType,
+        };
+
+        // no explicit `requires`, so the ordering comes from the function's argument/return types
+        let entities = vec![
+            SqlGraphEntity::ExtensionRoot(crate::__pgx_marker().unwrap()),
+            __pgx_internals_fn_varlena_type_identity(),
+            __pgx_internals_type_VarlenaType(),
+            __pgx_internals_fn_varlenatype_in(),
+            __pgx_internals_fn_varlenatype_out(),
+        ];
+        let sql = PgxSql::build(
+            DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(),
+            DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(),
+            entities.into_iter(),
+        )
+        .expect("failed to build the SQL entity graph")
+        .to_sql()
+        .expect("failed to generate SQL");
+
+        let create_type = sql
+            .find("CREATE TYPE VarlenaType (")
+            .expect("no CREATE TYPE for VarlenaType");
+        let create_function = sql
+            .find("FUNCTION \"varlena_type_identity\"")
+            .expect("no CREATE FUNCTION for varlena_type_identity");
+        assert!(create_type < create_function, "{}", sql);
+    }
+
     #[pg_test]
     fn test_serializedtype() {
         let result = Spi::get_one::<CustomTextFormatSerializedType>(
@@ -99,4 +209,68 @@ mod tests {
         assert_eq!(result.b, 2.0);
         assert_eq!(result.c, 3);
     }
+
+    const SHA256_HEX: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1eff";
+
+    fn sha256() -> Sha256 {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        bytes[31] = 0xff;
+        Sha256(bytes)
+    }
+
+    #[pg_test]
+    fn test_fixed_length_type_round_trip() {
+        assert_eq!(
+            Some(sha256()),
+            Spi::get_one::<Sha256>(&format!("SELECT sha256_identity('{}'::Sha256)", SHA256_HEX))
+        );
+        assert_eq!(
+            Some(SHA256_HEX.to_string()),
+            Spi::get_one_with_args::<String>(
+                "SELECT $1::text",
+                vec![(PgOid::from(Sha256::type_oid()), sha256().into_datum())],
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_fixed_length_type_in_table() {
+        Spi::run("CREATE TABLE tests.hashes (hash Sha256)");
+        Spi::run_with_args(
+            "INSERT INTO tests.hashes VALUES ($1), (NULL)",
+            vec![(PgOid::from(Sha256::type_oid()), sha256().into_datum())],
+        )
+        .expect("failed to insert");
+        assert_eq!(
+            Some(vec![Some(sha256()), None]),
+            Spi::get_one::<Vec<Option<Sha256>>>(
+                "SELECT array_agg(hash ORDER BY hash IS NULL) FROM tests.hashes"
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_fixed_length_type_definition() {
+        let (length, by_value) = Spi::get_two::<i16, bool>(
+            "SELECT typlen, typbyval FROM pg_type WHERE typname = 'sha256'",
+        );
+        assert_eq!(length, Some(32));
+        assert_eq!(by_value, Some(false));
+        assert_eq!(
+            Some(b'p' as i8),
+            Spi::get_one::<i8>("SELECT typstorage FROM pg_type WHERE typname = 'sha256'")
+        );
+    }
+
+    #[pg_test]
+    fn test_pg_type_storage_and_alignment() {
+        let (storage, alignment) = Spi::get_two::<i8, i8>(
+            "SELECT typstorage, typalign FROM pg_type WHERE typname = 'externalstoragetype'",
+        );
+        assert_eq!(storage, Some(b'e' as i8));
+        assert_eq!(alignment, Some(b'd' as i8));
+    }
 }
@@ -0,0 +1,81 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+// Postgres 10 has no procedures
+#![cfg(not(feature = "pg10"))]
+
+use pgx::*;
+
+/// Insert `1..=n` into the `procedure_rows` table, committing all but the even rows
+#[pg_proc]
+fn insert_odd_rows(n: i32) {
+    for i in 1..=n {
+        Spi::run(&format!("INSERT INTO procedure_rows VALUES ({})", i));
+        if i % 2 == 1 {
+            Spi::commit();
+        } else {
+            Spi::rollback();
+        }
+    }
+}
+
+#[pg_proc]
+fn insert_row(i: i32) {
+    Spi::run(&format!("INSERT INTO procedure_rows VALUES ({})", i));
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[test]
+    fn test_procedure_commits() {
+        // a `#[pg_test]` runs in a transaction, in which a procedure can't commit, so this `CALL`s
+        // it from a connection of its own
+        let (mut client, _) = pgx_tests::test_client(crate::pg_test::postgresql_conf_options());
+        client
+            .batch_execute(
+                "CREATE TEMPORARY TABLE procedure_rows (id integer);
+                 CALL insert_odd_rows(5);",
+            )
+            .expect("failed to call insert_odd_rows()");
+
+        let rows = client
+            .query("SELECT id FROM procedure_rows ORDER BY id", &[])
+            .expect("failed to select procedure_rows")
+            .iter()
+            .map(|row| row.get::<_, i32>(0))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![1, 3, 5]);
+    }
+
+    #[pg_test]
+    fn test_call_procedure() {
+        Spi::run("CREATE TABLE procedure_rows (id integer)");
+        Spi::run("CALL insert_row(42)");
+        assert_eq!(
+            Some(42),
+            Spi::get_one::<i32>("SELECT id FROM procedure_rows")
+        );
+    }
+
+    #[pg_test(error = "invalid transaction termination")]
+    fn test_procedure_commit_in_transaction() {
+        Spi::run("CREATE TABLE procedure_rows (id integer)");
+        Spi::run("CALL insert_odd_rows(1)");
+    }
+
+    #[pg_test]
+    fn test_procedure_signature() {
+        assert_eq!(
+            Some("p".to_string()),
+            Spi::get_one::<String>(
+                "SELECT prokind::text FROM pg_proc WHERE proname = 'insert_odd_rows'"
+            )
+        );
+    }
+}
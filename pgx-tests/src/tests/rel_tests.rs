@@ -0,0 +1,65 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    fn share_locks_held(relname: &str) -> Option<i64> {
+        Spi::get_one_with_args(
+            "SELECT count(*) FROM pg_locks \
+             WHERE locktype = 'relation' AND relation = $1::regclass AND mode = 'ShareLock' \
+             AND pid = pg_backend_pid()",
+            vec![(PgBuiltInOids::TEXTOID.oid(), relname.into_datum())],
+        )
+    }
+
+    #[pg_test]
+    fn test_open_with_name() {
+        Spi::run("CREATE TABLE tests.rel_open (id int, name text, value float8)");
+        let rel = PgRelation::open_with_name_and_share_lock("tests.rel_open")
+            .expect("failed to open relation");
+        assert_eq!(rel.name(), "rel_open");
+        assert_eq!(rel.namespace(), "tests");
+        assert!(rel.is_table());
+        assert_eq!(rel.tuple_desc().len(), 3);
+    }
+
+    #[pg_test]
+    fn test_open_with_name_nonexistent() {
+        assert_eq!(
+            Err("no such relation"),
+            PgRelation::open_with_name_and_share_lock("tests.rel_does_not_exist").map(|_| ())
+        );
+    }
+
+    #[pg_test]
+    fn test_relation_closes_on_drop() {
+        Spi::run("CREATE TABLE tests.rel_open (id int)");
+        let rel = PgRelation::open_with_name_and_share_lock("tests.rel_open")
+            .expect("failed to open relation");
+        let refcnt = rel.rd_refcnt;
+
+        let other = PgRelation::with_lock(rel.oid(), pg_sys::AccessShareLock as pg_sys::LOCKMODE);
+        assert_eq!(rel.rd_refcnt, refcnt + 1);
+        drop(other);
+        assert_eq!(rel.rd_refcnt, refcnt);
+    }
+
+    #[pg_test]
+    fn test_relation_releases_lock_on_drop() {
+        assert_eq!(Some(0), share_locks_held("pg_catalog.pg_am"));
+        let rel = PgRelation::open_with_name_and_lock(
+            "pg_catalog.pg_am",
+            pg_sys::ShareLock as pg_sys::LOCKMODE,
+        )
+        .expect("failed to open relation");
+        assert_eq!(Some(1), share_locks_held("pg_catalog.pg_am"));
+        drop(rel);
+        assert_eq!(Some(0), share_locks_held("pg_catalog.pg_am"));
+    }
+}
@@ -12,6 +12,14 @@ mod test_schema {
 
     #[derive(Debug, PostgresType, Serialize, Deserialize)]
     pub struct TestType(pub u64);
+
+    extension_sql!(
+        r#"
+CREATE FUNCTION {schema}.func_from_extension_sql() RETURNS text
+LANGUAGE sql AS $$ SELECT '{schema}'::text $$;
+"#,
+        name = "create_func_from_extension_sql"
+    );
 }
 
 #[pg_extern(schema = "test_schema")]
@@ -40,6 +48,14 @@ mod tests {
         Spi::run("SELECT test_schema.func_in_diff_schema2();");
     }
 
+    #[pg_test]
+    fn test_extension_sql_schema_placeholder() {
+        assert_eq!(
+            Spi::get_one::<&str>("SELECT test_schema.func_from_extension_sql();"),
+            Some("test_schema")
+        );
+    }
+
     #[pg_test]
     fn test_type_in_different_schema() {
         Spi::run("SELECT type_in_diff_schema();");
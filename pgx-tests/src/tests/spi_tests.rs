@@ -29,6 +29,35 @@ mod tests {
         });
     }
 
+    #[pg_test]
+    fn test_get_one_json() {
+        use serde_json::json;
+
+        assert_eq!(Some(json!(42)), Spi::get_one_json("SELECT 42"));
+        assert_eq!(
+            Some(json!("forty-two")),
+            Spi::get_one_json("SELECT 'forty-two'::text, 1")
+        );
+        assert_eq!(
+            Some(json!({"f1": 1, "f2": "two", "f3": [3.5]})),
+            Spi::get_one_json("SELECT ROW(1, 'two', ARRAY[3.5])")
+        );
+        assert_eq!(
+            Some(json!({"id": 7, "name": "seven"})),
+            Spi::get_one_json("SELECT t FROM (VALUES (7, 'seven')) t(id, name)")
+        );
+        // a type without a JSON equivalent is its text output
+        assert_eq!(
+            Some(json!("2021-01-02")),
+            Spi::get_one_json("SELECT '2021-01-02'::date")
+        );
+        // an untyped literal is text
+        assert_eq!(Some(json!("x")), Spi::get_one_json("SELECT 'x'"));
+        assert_eq!(None, Spi::get_one_json("SELECT NULL"));
+        assert_eq!(None, Spi::get_one_json("SELECT NULL::integer"));
+        assert_eq!(None, Spi::get_one_json("SELECT 1 WHERE false"));
+    }
+
     #[pg_test]
     fn test_spi_returns_primitive() {
         let rc = Spi::connect(|client| {
@@ -136,11 +165,538 @@ mod tests {
         });
     }
 
+    #[pg_test]
+    fn test_spi_get_one_falsy_values() {
+        assert_eq!(Some(false), Spi::get_one::<bool>("SELECT false"));
+        assert_eq!(Some(0), Spi::get_one::<i16>("SELECT 0::smallint"));
+        assert_eq!(Some(0), Spi::get_one::<i32>("SELECT 0"));
+        assert_eq!(Some(0), Spi::get_one::<i64>("SELECT 0::bigint"));
+        assert_eq!(Some(0.0), Spi::get_one::<f32>("SELECT 0::real"));
+        assert_eq!(Some(0.0), Spi::get_one::<f64>("SELECT 0::double precision"));
+        assert_eq!(Some(""), Spi::get_one::<&str>("SELECT ''"));
+        assert_eq!(Some(String::new()), Spi::get_one::<String>("SELECT ''"));
+    }
+
+    #[pg_test]
+    fn test_spi_get_one_nulls() {
+        assert_eq!(None, Spi::get_one::<bool>("SELECT NULL::bool"));
+        assert_eq!(None, Spi::get_one::<i16>("SELECT NULL::smallint"));
+        assert_eq!(None, Spi::get_one::<i32>("SELECT NULL::int"));
+        assert_eq!(None, Spi::get_one::<i64>("SELECT NULL::bigint"));
+        assert_eq!(None, Spi::get_one::<f32>("SELECT NULL::real"));
+        assert_eq!(None, Spi::get_one::<f64>("SELECT NULL::double precision"));
+        assert_eq!(None, Spi::get_one::<&str>("SELECT NULL::text"));
+        assert_eq!(None, Spi::get_one::<String>("SELECT NULL::text"));
+    }
+
     #[pg_test]
     fn test_spi_select_zero_rows() {
         assert!(Spi::get_one::<i32>("SELECT 1 LIMIT 0").is_none());
     }
 
+    #[pg_test]
+    fn test_spi_get_column() {
+        let column = Spi::get_column::<i32>("SELECT * FROM generate_series(1, 5)");
+        assert_eq!(vec![Some(1), Some(2), Some(3), Some(4), Some(5)], column);
+    }
+
+    #[pg_test]
+    fn test_spi_get_column_with_nulls() {
+        let column =
+            Spi::get_column::<String>("SELECT * FROM (VALUES ('a'), (NULL), ('c')) AS t(v)");
+        assert_eq!(
+            vec![Some("a".to_string()), None, Some("c".to_string())],
+            column
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_get_column_zero_rows() {
+        assert!(Spi::get_column::<i32>("SELECT 1 LIMIT 0").is_empty());
+    }
+
+    #[pg_test]
+    fn test_spi_column_metadata() {
+        Spi::execute(|client| {
+            let table = client.select("SELECT 1 AS a, 'x'::text AS b", None, None);
+
+            assert_eq!(2, table.column_count());
+            assert_eq!(Some("a".to_string()), table.column_name(1));
+            assert_eq!(Some("b".to_string()), table.column_name(2));
+            assert_eq!(None, table.column_name(3));
+            assert_eq!(Some(PgBuiltInOids::INT4OID.oid()), table.column_type(1));
+            assert_eq!(Some(PgBuiltInOids::TEXTOID.oid()), table.column_type(2));
+            assert_eq!(None, table.column_type(0));
+        });
+    }
+
+    #[pg_test]
+    fn test_spi_get_by_name() {
+        Spi::execute(|client| {
+            let table = client
+                .select("SELECT 1 AS answer, NULL::text AS nothing", None, None)
+                .first();
+            assert_eq!(Ok(Some(1)), table.get_by_name::<i32>("answer"));
+            assert_eq!(Ok(None), table.get_by_name::<String>("nothing"));
+            assert_eq!(Some(1), table.column_ordinal("answer"));
+            assert_eq!(
+                Err(SpiRowError::MissingColumn {
+                    column: "question".to_string()
+                }),
+                table.get_by_name::<i32>("question")
+            );
+            assert_eq!(None, table.column_ordinal("ctid"));
+        });
+    }
+
+    #[pg_test]
+    fn test_spi_get_text_by_name() {
+        Spi::execute(|client| {
+            let table = client
+                .select(
+                    "SELECT 42::numeric(5, 2) AS price, NULL::int AS nothing",
+                    None,
+                    None,
+                )
+                .first();
+            assert_eq!(
+                Ok(Some("42.00".to_string())),
+                table.get_text_by_name("price")
+            );
+            assert_eq!(Ok(None), table.get_text_by_name("nothing"));
+            assert!(table.get_text_by_name("question").is_err());
+        });
+    }
+
+    #[derive(Debug, PartialEq, FromDatum, IntoDatum)]
+    struct UserId(i64);
+
+    #[derive(Debug, PartialEq, FromDatum, IntoDatum)]
+    struct Email(String);
+
+    #[pg_test]
+    fn test_spi_get_one_newtype() {
+        assert_eq!(
+            Some(UserId(42)),
+            Spi::get_one::<UserId>("SELECT 42::bigint")
+        );
+        assert_eq!(None, Spi::get_one::<UserId>("SELECT NULL::bigint"));
+    }
+
+    #[pg_test]
+    fn test_spi_newtype_args() {
+        assert_eq!(
+            Some(Email("pgx@example.com".to_string())),
+            Spi::get_one_with_args::<Email>(
+                "SELECT $1 || '@example.com'",
+                vec![(
+                    PgOid::from(Email::type_oid()),
+                    Email("pgx".to_string()).into_datum()
+                )],
+            )
+        );
+        assert_eq!(
+            Some(vec![Some(UserId(1)), None]),
+            Spi::get_one_with_args::<Vec<Option<UserId>>>(
+                "SELECT ARRAY[$1, NULL]",
+                vec![(PgOid::from(UserId::type_oid()), UserId(1).into_datum())],
+            )
+        );
+    }
+
+    #[derive(Debug, PartialEq, SpiRow)]
+    struct Row {
+        a: i32,
+        b: String,
+    }
+
+    #[derive(Debug, PartialEq, SpiRow)]
+    struct NullableRow<'a> {
+        a: Option<i32>,
+        b: &'a str,
+    }
+
+    #[pg_test]
+    fn test_spi_into_typed() {
+        Spi::execute(|client| {
+            let rows = client
+                .select("SELECT 1 AS a, 'x' AS b", None, None)
+                .into_typed::<Row>();
+            assert_eq!(
+                Ok(vec![Row {
+                    a: 1,
+                    b: "x".to_string()
+                }]),
+                rows
+            );
+        });
+    }
+
+    #[pg_test]
+    fn test_spi_into_typed_many_rows() {
+        Spi::execute(|client| {
+            let rows = client
+                .select(
+                    "SELECT * FROM (VALUES (NULL, 'x'), (2, 'y')) AS t(a, b) ORDER BY b",
+                    None,
+                    None,
+                )
+                .into_typed::<NullableRow>();
+            assert_eq!(
+                Ok(vec![
+                    NullableRow { a: None, b: "x" },
+                    NullableRow { a: Some(2), b: "y" }
+                ]),
+                rows
+            );
+        });
+    }
+
+    #[pg_test]
+    fn test_spi_into_typed_errors() {
+        Spi::execute(|client| {
+            assert_eq!(
+                Err(SpiRowError::MissingColumn {
+                    column: "b".to_string()
+                }),
+                client
+                    .select("SELECT 1 AS a", None, None)
+                    .into_typed::<Row>()
+            );
+            assert_eq!(
+                Err(SpiRowError::TypeMismatch {
+                    column: "a".to_string(),
+                    expected: "integer".to_string(),
+                    actual: "bigint".to_string(),
+                }),
+                client
+                    .select("SELECT 1::bigint AS a, 'x' AS b", None, None)
+                    .into_typed::<Row>()
+            );
+            assert_eq!(
+                Err(SpiRowError::UnexpectedNull {
+                    column: "a".to_string()
+                }),
+                client
+                    .select("SELECT NULL::int AS a, 'x' AS b", None, None)
+                    .into_typed::<Row>()
+            );
+        });
+    }
+
+    #[pg_test]
+    fn test_spi_run_fast() {
+        Spi::run("CREATE TABLE tests.run_fast (id int)");
+        for i in 0..1000 {
+            Spi::run_fast("INSERT INTO tests.run_fast VALUES (42)");
+            assert_eq!(
+                Some(i + 1),
+                Spi::get_one::<i64>("SELECT count(*) FROM tests.run_fast")
+            );
+        }
+    }
+
+    #[pg_test]
+    fn test_spi_clear_cached_plans() {
+        Spi::clear_cached_plans();
+        Spi::run_fast("SELECT 1");
+        Spi::run_fast("SELECT 2");
+        Spi::run_fast("SELECT 1");
+        assert_eq!(2, Spi::clear_cached_plans());
+        assert_eq!(0, Spi::clear_cached_plans());
+        Spi::run_fast("SELECT 1");
+        assert_eq!(1, Spi::clear_cached_plans());
+    }
+
+    #[pg_test]
+    fn test_spi_run_fast_evicts_plans() {
+        Spi::clear_cached_plans();
+        for i in 0..200 {
+            Spi::run_fast(&format!("SELECT {}", i));
+        }
+        assert_eq!(128, Spi::clear_cached_plans());
+    }
+
+    #[pg_test]
+    fn test_spi_run_with_args() {
+        Spi::run("CREATE TABLE tests.run_with_args (id int, name text)");
+        let count = Spi::run_with_args(
+            "INSERT INTO tests.run_with_args VALUES ($1, $2)",
+            vec![
+                (PgBuiltInOids::INT4OID.oid(), 42.into_datum()),
+                (PgBuiltInOids::TEXTOID.oid(), "pgx".into_datum()),
+            ],
+        );
+        assert_eq!(1, count.expect("failed to insert"));
+        assert_eq!(
+            (Some(42), Some("pgx".to_string())),
+            Spi::get_two::<i32, String>("SELECT id, name FROM tests.run_with_args")
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_run_with_args_counts_rows() {
+        Spi::run("CREATE TABLE tests.run_with_args AS SELECT generate_series(1, 10) AS id");
+        let count = Spi::run_with_args(
+            "DELETE FROM tests.run_with_args WHERE id > $1",
+            vec![(PgBuiltInOids::INT4OID.oid(), 7.into_datum())],
+        );
+        assert_eq!(3, count.expect("failed to delete"));
+    }
+
+    #[pg_test]
+    fn test_spi_run_with_args_rejected() {
+        assert!(matches!(
+            Spi::run_with_args("COMMIT", vec![]),
+            Err(SpiError::Transaction)
+        ));
+    }
+
+    #[pg_test]
+    fn test_spi_insert_many() {
+        Spi::run("CREATE TABLE tests.insert_many (id int, name text)");
+        let count = Spi::insert_many(
+            "tests.insert_many",
+            &["id", "name"],
+            (1..=10_000).map(|i| vec![i.into_datum(), format!("row {}", i).into_datum()]),
+        );
+        assert_eq!(10_000, count);
+        assert_eq!(
+            (Some(10_000), Some(50_005_000)),
+            Spi::get_two::<i64, i64>("SELECT count(*), sum(id) FROM tests.insert_many")
+        );
+        assert_eq!(
+            Some("row 1234".to_string()),
+            Spi::get_one::<String>("SELECT name FROM tests.insert_many WHERE id = 1234")
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_insert_many_nulls() {
+        Spi::run("CREATE TABLE tests.insert_many (id int, name text)");
+        Spi::insert_many(
+            "tests.insert_many",
+            &["name", "id"],
+            vec![vec![None, 1.into_datum()], vec!["two".into_datum(), None]],
+        );
+        assert_eq!(
+            (Some(1), Some(1)),
+            Spi::get_two::<i64, i64>(
+                "SELECT count(*) FILTER (WHERE name IS NULL), count(*) FILTER (WHERE id IS NULL) \
+                 FROM tests.insert_many"
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_insert_many_quoted_table() {
+        Spi::run(r#"CREATE TABLE tests."Insert Many; --" (id int)"#);
+        let count = Spi::insert_many(
+            r#"tests."Insert Many; --""#,
+            &["id"],
+            vec![vec![1.into_datum()], vec![2.into_datum()]],
+        );
+        assert_eq!(2, count);
+        assert_eq!(
+            Some(3),
+            Spi::get_one::<i64>(r#"SELECT sum(id) FROM tests."Insert Many; --""#)
+        );
+    }
+
+    #[pg_test(error = "column \"nope\" of relation \"tests.insert_many\" does not exist")]
+    fn test_spi_insert_many_unknown_column() {
+        Spi::run("CREATE TABLE tests.insert_many (id int)");
+        Spi::insert_many("tests.insert_many", &["nope"], vec![vec![1.into_datum()]]);
+    }
+
+    #[pg_test]
+    fn test_spi_select_read_only() {
+        Spi::execute(|client| {
+            let i = client
+                .select_read_only("SELECT 42", None, None)
+                .first()
+                .get_one::<i32>();
+            assert_eq!(Some(42), i);
+        });
+    }
+
+    #[pg_test(error = "INSERT is not allowed in a non-volatile function")]
+    fn test_spi_run_read_only_rejects_insert() {
+        Spi::run("CREATE TABLE tests.read_only (id int)");
+        Spi::run_read_only("INSERT INTO tests.read_only VALUES (1)");
+    }
+
+    #[pg_test]
+    fn test_spi_with_subtransaction_commits_on_ok() {
+        Spi::run("CREATE TABLE tests.subxact (id int)");
+        let result = Spi::with_subtransaction(|| {
+            Spi::run("INSERT INTO tests.subxact VALUES (1)");
+            Ok::<_, ()>(42)
+        });
+        assert_eq!(Ok(42), result);
+        assert_eq!(
+            Some(1),
+            Spi::get_one::<i64>("SELECT count(*) FROM tests.subxact")
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_with_subtransaction_rolls_back_on_err() {
+        Spi::run("CREATE TABLE tests.subxact (id int)");
+        let result = Spi::with_subtransaction(|| {
+            Spi::run("INSERT INTO tests.subxact VALUES (1)");
+            Err::<(), _>("insert failed")
+        });
+        assert_eq!(Err("insert failed"), result);
+
+        // the outer transaction carries on as if the insert never happened
+        Spi::run("INSERT INTO tests.subxact VALUES (2)");
+        assert_eq!(
+            Some(2),
+            Spi::get_one::<i32>("SELECT sum(id)::int FROM tests.subxact")
+        );
+    }
+
+    #[pg_test(error = "invalid input syntax for type integer: \"oops\"")]
+    fn test_spi_with_subtransaction_rethrows_error() {
+        Spi::run("CREATE TABLE tests.subxact (id int)");
+        let _ = Spi::with_subtransaction(|| {
+            Spi::run("INSERT INTO tests.subxact VALUES ('oops')");
+            Ok::<_, ()>(())
+        });
+    }
+
+    #[pg_test]
+    fn test_assert_spi_eq() {
+        pgx_tests::assert_spi_eq!(42, "SELECT 42");
+        pgx_tests::assert_spi_eq!("pgx".to_string(), "SELECT 'pgx'");
+    }
+
+    #[pg_test(
+        error = "assert_spi_eq!() failed for query `SELECT 41`: expected `Some(42)`, got `Some(41)`\n ?column?\n----------\n 41\n(1 row)"
+    )]
+    fn test_assert_spi_eq_failure() {
+        pgx_tests::assert_spi_eq!(42, "SELECT 41");
+    }
+
+    #[pg_test(
+        error = "assert_spi_eq!() failed for query `SELECT x AS id, CASE WHEN x > 1 THEN 'many' END AS label FROM generate_series(1, 2) x ORDER BY x DESC`: expected `Some(1)`, got `Some(2)`\n id | label\n----+-------\n 2  | many\n 1  | NULL\n(2 rows)"
+    )]
+    fn test_assert_spi_eq_failure_shows_every_row() {
+        pgx_tests::assert_spi_eq!(
+            1,
+            "SELECT x AS id, CASE WHEN x > 1 THEN 'many' END AS label FROM generate_series(1, 2) x ORDER BY x DESC"
+        );
+    }
+
+    #[pg_test]
+    fn test_format_spi_result() {
+        assert_eq!(
+            pgx_tests::format_spi_result("SELECT 1 AS a, 'two' AS b WHERE false"),
+            " a | b\n---+---\n(0 rows)"
+        );
+        Spi::connect(|client| {
+            let table = client.select("SELECT 1, NULL::text UNION ALL SELECT 2, 'x'", None, None);
+            assert_eq!(
+                table.get_text_rows(),
+                vec![
+                    vec![Some("1".to_string()), None],
+                    vec![Some("2".to_string()), Some("x".to_string())]
+                ]
+            );
+            Ok(Some(()))
+        });
+    }
+
+    #[pg_extern]
+    fn spi_run_query(query: &str) {
+        Spi::run(query);
+    }
+
+    /// Runs `query` through [`spi_run_query`] and returns the ERROR's CONTEXT, as seen by plpgsql
+    fn spi_error_context(query: &str) -> String {
+        Spi::run(
+            "CREATE FUNCTION tests.capture_error_context(q text) RETURNS text LANGUAGE plpgsql AS $$
+                DECLARE ctx text;
+                BEGIN
+                    PERFORM tests.spi_run_query(q);
+                    RETURN NULL;
+                EXCEPTION WHEN others THEN
+                    GET STACKED DIAGNOSTICS ctx = PG_EXCEPTION_CONTEXT;
+                    RETURN ctx;
+                END
+            $$",
+        );
+        Spi::get_one_with_args::<String>(
+            "SELECT tests.capture_error_context($1)",
+            vec![(PgBuiltInOids::TEXTOID.oid(), query.into_datum())],
+        )
+        .expect("query did not raise an ERROR")
+    }
+
+    #[pg_test]
+    fn test_spi_error_context_includes_query() {
+        let context = spi_error_context("THIS IS NOT A VALID QUERY");
+        assert!(
+            context.contains("Spi query: \"THIS IS NOT A VALID QUERY\""),
+            "{}",
+            context
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_error_context_truncates_long_query() {
+        let query = format!("SELECT 1/0 AS {}", "x".repeat(2000));
+        let context = spi_error_context(&query);
+        assert!(
+            context.contains(&format!("Spi query: \"{}...\"", &query[..1024])),
+            "{}",
+            context
+        );
+    }
+
+    #[pg_test]
+    fn test_spi_disconnects_on_panic() {
+        let result = pg_try(|| {
+            Spi::execute(|client| {
+                client.select("SELECT 1", None, None);
+                panic!("panic while connected to SPI");
+            })
+        });
+        unsafe { result.unwrap_or(()) };
+
+        // nothing should be left connected
+        assert_eq!(-(SpiError::Unconnected as i32), unsafe {
+            pg_sys::SPI_finish()
+        });
+        assert_eq!(Some(1), Spi::get_one::<i32>("SELECT 1"));
+    }
+
+    /// Assert that no other test is running, in any of the test databases, after giving one that
+    /// overlaps this test time to start
+    fn assert_no_other_test_running() {
+        Spi::run("SELECT pg_sleep(0.2), pg_stat_clear_snapshot()");
+        assert_eq!(
+            Some(0),
+            Spi::get_one::<i64>(
+                "SELECT count(*) FROM pg_stat_activity \
+                 WHERE pid <> pg_backend_pid() \
+                   AND state = 'active' \
+                   AND query LIKE 'SELECT \"tests\".%'"
+            )
+        );
+    }
+
+    #[pg_test(serial)]
+    fn test_serial_runs_alone() {
+        assert_no_other_test_running();
+    }
+
+    #[pg_test(serial)]
+    fn test_serial_runs_alone_too() {
+        assert_no_other_test_running();
+    }
+
     #[pg_extern]
     fn do_panic() {
         panic!("did a panic");
@@ -61,6 +61,28 @@ fn return_none_setof_iterator() -> Option<impl std::iter::Iterator<Item = i32>>
     }
 }
 
+/// The first `n` positive multiples of `factor`, or no rows at all for a `factor` of zero
+#[pg_extern]
+fn optional_multiples(factor: i32, n: i32) -> Option<impl std::iter::Iterator<Item = i32>> {
+    if factor == 0 {
+        None
+    } else {
+        Some((1..=n).map(move |i| i * factor))
+    }
+}
+
+#[pg_extern]
+fn optional_table_multiples(
+    factor: i32,
+    n: i32,
+) -> Option<impl std::iter::Iterator<Item = (name!(i, i32), name!(multiple, i32))>> {
+    if factor == 0 {
+        None
+    } else {
+        Some((1..=n).map(move |i| (i, i * factor)))
+    }
+}
+
 #[cfg(any(test, feature = "pg_test"))]
 #[pgx::pg_schema]
 mod tests {
@@ -149,6 +171,39 @@ mod tests {
         assert_eq!(cnt, Some(3))
     }
 
+    #[pg_test]
+    fn test_optional_setof_iterator_per_input() {
+        // the SRF starts over for each factor, whether or not the previous one returned any rows
+        assert_eq!(
+            Some(vec![2, 4, 6, 3, 6, 9]),
+            Spi::get_one::<Vec<i32>>(
+                "SELECT array_agg(m ORDER BY ord, m)
+                   FROM unnest(ARRAY[0, 2, 0, 3, 0]) WITH ORDINALITY AS f(factor, ord),
+                        LATERAL optional_multiples(factor, 3) m"
+            )
+        );
+        assert_eq!(
+            Some(0),
+            Spi::get_one::<i64>("SELECT count(*) FROM optional_multiples(0, 3)")
+        );
+    }
+
+    #[pg_test]
+    fn test_optional_table_iterator_per_input() {
+        assert_eq!(
+            Some(vec![5, 10, 7, 14]),
+            Spi::get_one::<Vec<i32>>(
+                "SELECT array_agg(t.multiple ORDER BY ord, t.i)
+                   FROM unnest(ARRAY[5, 0, 7]) WITH ORDINALITY AS f(factor, ord),
+                        LATERAL optional_table_multiples(factor, 2) t"
+            )
+        );
+        assert_eq!(
+            Some(0),
+            Spi::get_one::<i64>("SELECT count(*) FROM optional_table_multiples(0, 2)")
+        );
+    }
+
     #[pg_test]
     fn test_return_none_setof_iterator() {
         let cnt = Spi::connect(|client| {
@@ -0,0 +1,54 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_stringinfo_with_capacity() {
+        let sinfo = StringInfo::with_capacity(4096);
+        assert!(sinfo.capacity() > 4096);
+        assert!(sinfo.is_empty());
+    }
+
+    #[pg_test]
+    fn test_stringinfo_reserve_keeps_contents() {
+        let mut sinfo = StringInfo::from("hello");
+        sinfo.reserve(4096);
+        assert!(sinfo.capacity() > sinfo.len() + 4096);
+        assert_eq!(sinfo.to_string(), "hello");
+    }
+
+    #[pg_test]
+    fn test_stringinfo_as_str() {
+        let mut sinfo = StringInfo::new();
+        assert_eq!(sinfo.as_str(), Ok(""));
+        sinfo.push_str("hello");
+        assert_eq!(sinfo.as_bytes(), b"hello");
+        assert_eq!(sinfo.as_str(), Ok("hello"));
+
+        sinfo.push_bytes(&[0xff]);
+        assert!(sinfo.as_str().is_err());
+    }
+
+    #[pg_test]
+    fn test_stringinfo_reserve_avoids_reallocation() {
+        let mut sinfo = StringInfo::new();
+        sinfo.reserve(4096);
+        let capacity = sinfo.capacity();
+        let ptr = sinfo.as_ptr();
+
+        for _ in 0..4096 {
+            sinfo.push('x');
+        }
+
+        assert_eq!(sinfo.len(), 4096);
+        assert_eq!(sinfo.capacity(), capacity);
+        assert_eq!(sinfo.as_ptr(), ptr);
+    }
+}
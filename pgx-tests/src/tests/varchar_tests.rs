@@ -0,0 +1,51 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_extern]
+    fn take_and_return_varchar(value: Varchar) -> Varchar {
+        value
+    }
+
+    #[pg_extern]
+    fn varchar_3(value: &str) -> Varchar {
+        Varchar::with_max_length(value, 3)
+    }
+
+    #[pg_test]
+    fn test_varchar_signature() {
+        let signature = Spi::get_one::<String>(
+            "SELECT pg_get_function_identity_arguments(oid) || ' -> ' || prorettype::regtype
+               FROM pg_proc WHERE proname = 'take_and_return_varchar'",
+        );
+        assert_eq!(
+            Some("value character varying -> character varying".to_string()),
+            signature
+        );
+    }
+
+    #[pg_test]
+    fn test_take_and_return_varchar() {
+        let value = Spi::get_one::<Varchar>("SELECT tests.take_and_return_varchar('pgx')");
+        assert_eq!(Some(Varchar("pgx".to_string())), value);
+    }
+
+    #[pg_test]
+    fn test_varchar_within_max_length() {
+        // `max_length` counts characters, not bytes
+        let value = Spi::get_one::<String>("SELECT tests.varchar_3('日本語')");
+        assert_eq!(Some("日本語".to_string()), value);
+    }
+
+    #[pg_test(error = "value too long for type character varying(3)")]
+    fn test_varchar_exceeds_max_length() {
+        Spi::get_one::<String>("SELECT tests.varchar_3('pgxx')");
+    }
+}
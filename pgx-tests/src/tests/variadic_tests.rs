@@ -9,6 +9,11 @@ mod test {
     fn func_with_variadic_array_args(_field: &str, values: VariadicArray<&str>) -> String {
         values.get(0).unwrap().unwrap().to_string()
     }
+
+    #[pg_extern]
+    fn concat_all(parts: variadic!(&str)) -> String {
+        parts.iter().flatten().collect()
+    }
 }
 
 #[cfg(any(test, feature = "pg_test"))]
@@ -27,4 +32,32 @@ mod tests {
         .expect("didn't get SPI result");
         assert_eq!(result, "a");
     }
+
+    #[pg_test]
+    fn test_variadic_macro_args() {
+        assert_eq!(
+            Some("a".to_string()),
+            Spi::get_one::<String>("SELECT test.concat_all('a')")
+        );
+        assert_eq!(
+            Some("abc".to_string()),
+            Spi::get_one::<String>("SELECT test.concat_all('a', 'b', 'c')")
+        );
+        assert_eq!(
+            Some("ac".to_string()),
+            Spi::get_one::<String>("SELECT test.concat_all('a', NULL, 'c')")
+        );
+        assert_eq!(
+            Some("xy".to_string()),
+            Spi::get_one::<String>("SELECT test.concat_all(VARIADIC ARRAY['x', 'y'])")
+        );
+    }
+
+    #[pg_test]
+    fn test_variadic_macro_signature() {
+        assert_eq!(
+            Some("VARIADIC parts text[]".to_string()),
+            Spi::get_one::<String>("SELECT pg_get_function_arguments('test.concat_all'::regproc)")
+        );
+    }
 }
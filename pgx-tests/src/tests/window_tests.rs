@@ -0,0 +1,99 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use pgx::*;
+
+/// Works like Postgres' own `row_number()`, counting the rows of the partition in its local memory
+#[pg_extern(window)]
+fn my_row_number(fcinfo: pg_sys::FunctionCallInfo) -> i64 {
+    let mut window =
+        unsafe { PgWindowObject::from_fcinfo(fcinfo) }.expect("not called as a window function");
+    // no earlier rows are needed
+    window.set_mark_position(window.current_position());
+
+    let row_number = window.partition_local_memory::<i64>();
+    *row_number += 1;
+    *row_number
+}
+
+#[pg_extern(window)]
+fn my_lag(_value: i64, fcinfo: pg_sys::FunctionCallInfo) -> Option<i64> {
+    let window =
+        unsafe { PgWindowObject::from_fcinfo(fcinfo) }.expect("not called as a window function");
+    window.get_func_arg_in_partition(0, -1, WindowSeek::Current, false)
+}
+
+#[pg_extern(window)]
+fn my_partition_size(fcinfo: pg_sys::FunctionCallInfo) -> i64 {
+    let window =
+        unsafe { PgWindowObject::from_fcinfo(fcinfo) }.expect("not called as a window function");
+    window.partition_row_count()
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pgx::pg_schema]
+mod tests {
+    #[allow(unused_imports)]
+    use crate as pgx_tests;
+
+    use pgx::*;
+
+    #[pg_test]
+    fn test_window_row_number() {
+        assert_eq!(
+            Some(vec![1, 2, 3, 1, 2, 1]),
+            Spi::get_one::<Vec<i64>>(
+                "SELECT array_agg(n ORDER BY x) FROM (
+                    SELECT x, my_row_number() OVER (PARTITION BY x / 10 ORDER BY x) AS n
+                    FROM unnest(ARRAY[1, 2, 3, 11, 12, 21]) x
+                ) numbered"
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_window_row_number_matches_builtin() {
+        assert_eq!(
+            Some(true),
+            Spi::get_one::<bool>(
+                "SELECT bool_and(mine = theirs) FROM (
+                    SELECT my_row_number() OVER w AS mine, row_number() OVER w AS theirs
+                    FROM generate_series(1, 1000) x
+                    WINDOW w AS (PARTITION BY x % 7 ORDER BY x DESC)
+                ) numbered"
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_window_get_func_arg_in_partition() {
+        assert_eq!(
+            Some(vec![None, Some(1), Some(2), None, Some(11)]),
+            Spi::get_one::<Vec<Option<i64>>>(
+                "SELECT array_agg(previous ORDER BY x) FROM (
+                    SELECT x, my_lag(x) OVER (PARTITION BY x / 10 ORDER BY x) AS previous
+                    FROM unnest(ARRAY[1, 2, 3, 11, 12]::bigint[]) x
+                ) lagged"
+            )
+        );
+    }
+
+    #[pg_test]
+    fn test_window_partition_row_count() {
+        assert_eq!(
+            Some(true),
+            Spi::get_one::<bool>(
+                "SELECT bool_and(mine = theirs) FROM (
+                    SELECT my_partition_size() OVER w AS mine, count(*) OVER w AS theirs
+                    FROM generate_series(1, 100) x
+                    WINDOW w AS (PARTITION BY x % 3)
+                ) sized"
+            )
+        );
+    }
+
+    #[pg_test(error = "window function my_row_number requires an OVER clause")]
+    fn test_window_requires_over() {
+        Spi::get_one::<i64>("SELECT my_row_number()");
+    }
+}
@@ -194,10 +194,15 @@ pub enum ExternArgs {
     ParallelSafe,
     ParallelUnsafe,
     ParallelRestricted,
+    Leakproof,
+    Window,
+    Procedure,
+    Serial,
     Error(String),
     Schema(String),
     Name(String),
     Requires(Vec<PositioningRef>),
+    Support(PositioningRef),
 }
 
 impl core::fmt::Display for ExternArgs {
@@ -211,11 +216,16 @@ impl core::fmt::Display for ExternArgs {
             ExternArgs::ParallelSafe => write!(f, "PARALLEL SAFE"),
             ExternArgs::ParallelUnsafe => write!(f, "PARALLEL UNSAFE"),
             ExternArgs::ParallelRestricted => write!(f, "PARALLEL RESTRICTED"),
+            ExternArgs::Leakproof => write!(f, "LEAKPROOF"),
+            ExternArgs::Window => write!(f, "WINDOW"),
+            ExternArgs::Procedure => Ok(()),
+            ExternArgs::Serial => Ok(()),
             ExternArgs::Error(_) => Ok(()),
             ExternArgs::NoGuard => Ok(()),
             ExternArgs::Schema(_) => Ok(()),
             ExternArgs::Name(_) => Ok(()),
             ExternArgs::Requires(_) => Ok(()),
+            ExternArgs::Support(_) => Ok(()),
         }
     }
 }
@@ -232,6 +242,10 @@ impl ToTokens for ExternArgs {
             ExternArgs::ParallelSafe => tokens.append(format_ident!("ParallelSafe")),
             ExternArgs::ParallelUnsafe => tokens.append(format_ident!("ParallelUnsafe")),
             ExternArgs::ParallelRestricted => tokens.append(format_ident!("ParallelRestricted")),
+            ExternArgs::Leakproof => tokens.append(format_ident!("Leakproof")),
+            ExternArgs::Window => tokens.append(format_ident!("Window")),
+            ExternArgs::Procedure => tokens.append(format_ident!("Procedure")),
+            ExternArgs::Serial => tokens.append(format_ident!("Serial")),
             ExternArgs::Error(_s) => {
                 tokens.append_all(
                     quote! {
@@ -264,6 +278,14 @@ impl ToTokens for ExternArgs {
                     .to_token_stream(),
                 );
             }
+            ExternArgs::Support(item) => {
+                tokens.append_all(
+                    quote! {
+                        Support(#item)
+                    }
+                    .to_token_stream(),
+                );
+            }
         }
     }
 }
@@ -303,6 +325,10 @@ pub fn parse_extern_attributes(attr: TokenStream) -> HashSet<ExternArgs> {
                     "parallel_safe" => args.insert(ExternArgs::ParallelSafe),
                     "parallel_unsafe" => args.insert(ExternArgs::ParallelUnsafe),
                     "parallel_restricted" => args.insert(ExternArgs::ParallelRestricted),
+                    "leakproof" => args.insert(ExternArgs::Leakproof),
+                    "window" => args.insert(ExternArgs::Window),
+                    "procedure" => args.insert(ExternArgs::Procedure),
+                    "serial" => args.insert(ExternArgs::Serial),
                     "error" => {
                         let _punc = itr.next().unwrap();
                         let literal = itr.next().unwrap();
@@ -488,6 +514,29 @@ pub fn categorize_trait_bound(bound: &TypeParamBound) -> CategorizedType {
     }
 }
 
+/// The `T` of an `inout!(T)` argument type, or `None` if `ty` isn't one.
+///
+/// Like `variadic!()`, this keys on the final `inout` segment of the macro's path, which must
+/// mention `pgx` if it's qualified at all.
+pub fn inout_inner_type(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Macro(type_macro) => {
+            let path = &type_macro.mac.path;
+            match path.segments.last() {
+                Some(last)
+                    if last.ident == "inout"
+                        && ((path.leading_colon.is_none() && path.segments.len() == 1)
+                            || path.segments.iter().any(|segment| segment.ident == "pgx")) =>
+                {
+                    type_macro.mac.parse_body::<Type>().ok()
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 pub fn anonymonize_lifetimes_in_type_path(value: syn::TypePath) -> syn::TypePath {
     let mut ty = syn::Type::Path(value);
     anonymonize_lifetimes(&mut ty);
@@ -570,4 +619,31 @@ mod tests {
             "syntax error at or near \"THIS\"".to_string()
         )));
     }
+
+    #[test]
+    fn parse_leakproof() {
+        let ts = proc_macro2::TokenStream::from_str("immutable, leakproof").unwrap();
+
+        let args = parse_extern_attributes(ts);
+        assert!(args.contains(&ExternArgs::Leakproof));
+        assert_eq!(ExternArgs::Leakproof.to_string(), "LEAKPROOF");
+    }
+
+    #[test]
+    fn parse_window() {
+        let ts = proc_macro2::TokenStream::from_str("window").unwrap();
+
+        let args = parse_extern_attributes(ts);
+        assert!(args.contains(&ExternArgs::Window));
+        assert_eq!(ExternArgs::Window.to_string(), "WINDOW");
+    }
+
+    #[test]
+    fn parse_serial() {
+        let ts = proc_macro2::TokenStream::from_str("serial, error = \"oops\"").unwrap();
+
+        let args = parse_extern_attributes(ts);
+        assert!(args.contains(&ExternArgs::Serial));
+        assert!(args.contains(&ExternArgs::Error("oops".to_string())));
+    }
 }
@@ -16,6 +16,7 @@ pub struct Argument {
     pat: syn::Ident,
     ty: syn::Type,
     default: Option<String>,
+    is_inout: bool,
 }
 
 impl Argument {
@@ -29,6 +30,13 @@ impl Argument {
     pub fn build_from_pat_type(value: syn::PatType) -> Result<Option<Self>, syn::Error> {
         let mut true_ty = *value.ty.clone();
         anonymonize_lifetimes(&mut true_ty);
+        let is_inout = match crate::inout_inner_type(&true_ty) {
+            Some(inner) => {
+                true_ty = inner;
+                true
+            }
+            None => false,
+        };
 
         let identifier = match *value.pat {
             Pat::Ident(ref p) => p.ident.clone(),
@@ -150,6 +158,7 @@ impl Argument {
             pat: identifier,
             ty: true_ty,
             default,
+            is_inout,
         }))
     }
 }
@@ -253,6 +262,7 @@ impl ToTokens for Argument {
         let mut found_optional = false;
         let mut found_variadic = false;
         let pat = &self.pat;
+        let is_inout = self.is_inout;
         let default = self.default.iter();
         let mut ty = self.ty.clone();
         anonymonize_lifetimes(&mut ty);
@@ -270,15 +280,8 @@ impl ToTokens for Argument {
                     }
                 }
             }
-            syn::Type::Macro(ref type_macro) => {
-                let path = &type_macro.mac.path;
-                for segment in &path.segments {
-                    let ident_string = segment.ident.to_string();
-                    match ident_string.as_str() {
-                        "variadic" => found_variadic = true,
-                        _ => (),
-                    }
-                }
+            syn::Type::Macro(ref type_macro) if is_variadic_macro(&type_macro.mac.path) => {
+                found_variadic = true
             }
             _ => (),
         };
@@ -298,6 +301,7 @@ impl ToTokens for Argument {
                 },
                 is_optional: #found_optional,
                 is_variadic: #found_variadic,
+                is_inout: #is_inout,
                 default: None#( .unwrap_or(Some(#default)) )*,
             }
         };
@@ -305,6 +309,22 @@ impl ToTokens for Argument {
     }
 }
 
+/// Determine if `path` names the `pgx::variadic!()` macro, however it was spelled.
+///
+/// This keys on the final `variadic` segment, so `variadic!()` (via `use pgx::*`),
+/// `pgx::variadic!()`, `::pgx::variadic!()` and re-exports such as `pgx::prelude::variadic!()`
+/// are all recognized.  Qualified paths must mention `pgx` somewhere so that another crate's
+/// `variadic!()` isn't mistaken for ours.
+fn is_variadic_macro(path: &syn::Path) -> bool {
+    match path.segments.last() {
+        Some(last) if last.ident == "variadic" => {
+            (path.leading_colon.is_none() && path.segments.len() == 1)
+                || path.segments.iter().any(|segment| segment.ident == "pgx")
+        }
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct DefaultMacro {
     ty: syn::Type,
@@ -321,3 +341,83 @@ impl Parse for DefaultMacro {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Argument;
+    use quote::ToTokens;
+    use syn::parse_quote;
+
+    fn is_variadic(arg: syn::FnArg) -> bool {
+        let arg = Argument::build(arg)
+            .expect("could not build argument")
+            .expect("argument was skipped");
+        let tokens = arg.to_token_stream().to_string();
+        if tokens.contains("is_variadic : true") {
+            true
+        } else if tokens.contains("is_variadic : false") {
+            false
+        } else {
+            panic!("no `is_variadic` in {}", tokens)
+        }
+    }
+
+    #[test]
+    fn variadic_bare() {
+        assert!(is_variadic(parse_quote! { values: variadic!(i32) }));
+    }
+
+    #[test]
+    fn variadic_crate_path() {
+        assert!(is_variadic(parse_quote! { values: pgx::variadic!(i32) }));
+    }
+
+    #[test]
+    fn variadic_absolute_path() {
+        assert!(is_variadic(parse_quote! { values: ::pgx::variadic!(i32) }));
+    }
+
+    #[test]
+    fn variadic_reexported_path() {
+        assert!(is_variadic(
+            parse_quote! { values: pgx::prelude::variadic!(i32) }
+        ));
+        assert!(is_variadic(
+            parse_quote! { values: ::pgx::prelude::variadic!(i32) }
+        ));
+    }
+
+    #[test]
+    fn variadic_array() {
+        assert!(is_variadic(parse_quote! { values: VariadicArray<&str> }));
+    }
+
+    #[test]
+    fn inout() {
+        for arg in [
+            parse_quote! { value: inout!(i32) },
+            parse_quote! { value: pgx::inout!(Option<i32>) },
+        ] {
+            let tokens = Argument::build(arg)
+                .expect("could not build argument")
+                .expect("argument was skipped")
+                .to_token_stream()
+                .to_string();
+            assert!(tokens.contains("is_inout : true"), "{}", tokens);
+            assert!(!tokens.contains("inout !"), "{}", tokens);
+        }
+
+        let arg: syn::FnArg = parse_quote! { value: i32 };
+        let tokens = Argument::build(arg).unwrap().unwrap().to_token_stream();
+        assert!(tokens.to_string().contains("is_inout : false"));
+    }
+
+    #[test]
+    fn not_variadic() {
+        assert!(!is_variadic(parse_quote! { values: Vec<i32> }));
+        assert!(!is_variadic(parse_quote! { values: other::variadic!(i32) }));
+        assert!(!is_variadic(
+            parse_quote! { values: pgx::variadic::other!(i32) }
+        ));
+    }
+}
@@ -41,10 +41,15 @@ pub enum Attribute {
     ParallelSafe,
     ParallelUnsafe,
     ParallelRestricted,
+    Leakproof,
+    Window,
+    Procedure,
+    Serial,
     Error(syn::LitStr),
     Schema(syn::LitStr),
     Name(syn::LitStr),
     Requires(Punctuated<PositioningRef, Token![,]>),
+    Support(PositioningRef),
 }
 
 impl ToTokens for Attribute {
@@ -65,6 +70,12 @@ impl ToTokens for Attribute {
             Attribute::ParallelRestricted => {
                 quote! { pgx::datum::sql_entity_graph::ExternArgs::ParallelRestricted }
             }
+            Attribute::Leakproof => quote! { pgx::datum::sql_entity_graph::ExternArgs::Leakproof },
+            Attribute::Window => quote! { pgx::datum::sql_entity_graph::ExternArgs::Window },
+            Attribute::Procedure => {
+                quote! { pgx::datum::sql_entity_graph::ExternArgs::Procedure }
+            }
+            Attribute::Serial => quote! { pgx::datum::sql_entity_graph::ExternArgs::Serial },
             Attribute::Error(s) => {
                 quote! { pgx::datum::sql_entity_graph::ExternArgs::Error(String::from(#s)) }
             }
@@ -81,6 +92,9 @@ impl ToTokens for Attribute {
                     .collect::<Vec<_>>();
                 quote! { pgx::datum::sql_entity_graph::ExternArgs::Requires(vec![#(#items_iter),*],) }
             }
+            Attribute::Support(item) => {
+                quote! { pgx::datum::sql_entity_graph::ExternArgs::Support(#item) }
+            }
         };
         tokens.append_all(quoted);
     }
@@ -99,6 +113,10 @@ impl Parse for Attribute {
             "parallel_safe" => Self::ParallelSafe,
             "parallel_unsafe" => Self::ParallelUnsafe,
             "parallel_restricted" => Self::ParallelRestricted,
+            "leakproof" => Self::Leakproof,
+            "window" => Self::Window,
+            "procedure" => Self::Procedure,
+            "serial" => Self::Serial,
             "error" => {
                 let _eq: Token![=] = input.parse()?;
                 let literal: syn::LitStr = input.parse()?;
@@ -120,6 +138,12 @@ impl Parse for Attribute {
                 let _bracket = syn::bracketed!(content in input);
                 Self::Requires(content.parse_terminated(PositioningRef::parse)?)
             }
+            "support" => {
+                let _eq: Token![=] = input.parse()?;
+                let path: syn::Path = input.parse()?;
+                let path_str = path.to_token_stream().to_string().replace(" ", "");
+                Self::Support(PositioningRef::FullPath(path_str))
+            }
             _ => return Err(syn::Error::new(Span::call_site(), "Invalid option")),
         };
         Ok(found)
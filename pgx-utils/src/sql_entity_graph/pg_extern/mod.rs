@@ -72,6 +72,14 @@ impl PgExtern {
         self.attrs.as_ref()
     }
 
+    /// Is this a `#[pg_proc]`, created with `CREATE PROCEDURE`?
+    pub fn is_procedure(&self) -> bool {
+        matches!(
+            &self.attrs,
+            Some(attrs) if attrs.attrs.iter().any(|attr| attr == &Attribute::Procedure)
+        )
+    }
+
     pub fn extern_attr_tokens(&self) -> &proc_macro2::TokenStream {
         &self.attr_tokens
     }
@@ -190,8 +198,89 @@ impl PgExtern {
     }
 
     pub fn new(attr: TokenStream2, item: TokenStream2) -> Result<Self, syn::Error> {
-        let attrs = syn::parse2::<PgxAttributes>(attr.clone()).ok();
+        let attrs = match syn::parse2::<PgxAttributes>(attr.clone()) {
+            Ok(attrs) => Some(attrs),
+            // a `#[pg_proc]` whose options can't be understood would otherwise be created as a
+            // function, without any of them
+            Err(error) if is_procedure_attr(&attr) => return Err(error),
+            Err(_) => None,
+        };
         let func = syn::parse2::<syn::ItemFn>(item)?;
+
+        if let Some(attrs) = &attrs {
+            let has = |wanted: Attribute| attrs.attrs.iter().any(|attr| attr == &wanted);
+            // Postgres would accept a leakproof volatile function, but its documentation is clear
+            // that only functions with no side effects should be considered leakproof
+            if has(Attribute::Leakproof) && !(has(Attribute::Immutable) || has(Attribute::Stable)) {
+                return Err(syn::Error::new_spanned(
+                    &func.sig.ident,
+                    "a `leakproof` function must also be `immutable` or `stable`",
+                ));
+            }
+
+            // a procedure has none of the properties of a function that Postgres uses to plan
+            // calls to it
+            if has(Attribute::Procedure) {
+                let unsupported = attrs.attrs.iter().any(|attr| {
+                    !matches!(
+                        attr,
+                        Attribute::Procedure
+                            | Attribute::NoGuard
+                            | Attribute::Schema(_)
+                            | Attribute::Name(_)
+                            | Attribute::Requires(_)
+                    )
+                });
+                if unsupported {
+                    return Err(syn::Error::new_spanned(
+                        &func.sig.ident,
+                        "a procedure only accepts the `schema`, `name`, `requires` and `no_guard` options",
+                    ));
+                }
+                let returns_unit = match &func.sig.output {
+                    syn::ReturnType::Default => true,
+                    syn::ReturnType::Type(_, ty) => {
+                        matches!(ty.as_ref(), syn::Type::Tuple(tuple) if tuple.elems.is_empty())
+                    }
+                };
+                if !returns_unit {
+                    return Err(syn::Error::new_spanned(
+                        &func.sig.output,
+                        "a procedure can't return a value",
+                    ));
+                }
+            }
+        }
+
+        // Postgres returns the new values of `INOUT` parameters in place of anything else
+        let inouts = func
+            .sig
+            .inputs
+            .iter()
+            .filter(|input| match input {
+                syn::FnArg::Typed(pat) => crate::inout_inner_type(&pat.ty).is_some(),
+                _ => false,
+            })
+            .count();
+        if inouts > 0 {
+            let returned = match &func.sig.output {
+                syn::ReturnType::Default => 0,
+                syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+                    syn::Type::Tuple(tuple) => tuple.elems.len(),
+                    _ => 1,
+                },
+            };
+            if returned != inouts {
+                return Err(syn::Error::new_spanned(
+                    &func.sig.output,
+                    format!(
+                        "a function with {} `inout!()` argument(s) must return the new value of each, but returns {} value(s)",
+                        inouts, returned
+                    ),
+                ));
+            }
+        }
+
         Ok(Self {
             attrs: attrs,
             attr_tokens: attr,
@@ -200,6 +289,14 @@ impl PgExtern {
     }
 }
 
+/// Does `attr` begin with the `procedure` option that `#[pg_proc]` adds to its attributes?
+fn is_procedure_attr(attr: &TokenStream2) -> bool {
+    matches!(
+        attr.clone().into_iter().next(),
+        Some(proc_macro2::TokenTree::Ident(ident)) if ident == "procedure"
+    )
+}
+
 impl ToTokens for PgExtern {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let ident = &self.func.sig.ident;
@@ -262,3 +359,85 @@ impl Parse for PgExtern {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PgExtern;
+    use quote::quote;
+
+    #[test]
+    fn inout_returns_each_new_value() {
+        for func in [
+            quote! { fn example(a: inout!(i32)) -> i32 { a } },
+            quote! { fn example(a: inout!(i32), b: i32, c: inout!(i32)) -> (i32, i32) { (a, c) } },
+        ] {
+            assert!(PgExtern::new(quote! {}, func).is_ok());
+        }
+        for func in [
+            quote! { fn example(a: inout!(i32)) { } },
+            quote! { fn example(a: inout!(i32), b: inout!(i32)) -> i32 { a } },
+        ] {
+            let error = PgExtern::new(quote! {}, func)
+                .err()
+                .expect("an inout function with the wrong return was accepted");
+            assert!(
+                error
+                    .to_string()
+                    .contains("must return the new value of each"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn procedure_options_and_return() {
+        let func = quote! { fn example(a: i32) { } };
+        for attr in [quote! { procedure }, quote! { procedure, schema = "s" }] {
+            assert!(PgExtern::new(attr.clone(), func.clone())
+                .expect("a procedure was rejected")
+                .is_procedure());
+        }
+        assert!(!PgExtern::new(quote! {}, func.clone())
+            .unwrap()
+            .is_procedure());
+
+        let error = PgExtern::new(quote! { procedure, immutable }, func.clone())
+            .err()
+            .expect("an immutable procedure was accepted");
+        assert!(error.to_string().contains("only accepts"), "{}", error);
+
+        assert!(PgExtern::new(quote! { procedure, sql = "SELECT 1" }, func.clone()).is_err());
+        assert!(PgExtern::new(quote! { procedure, error = "oops" }, func.clone()).is_err());
+
+        let error = PgExtern::new(quote! { procedure }, quote! { fn example() -> i32 { 1 } })
+            .err()
+            .expect("a procedure returning a value was accepted");
+        assert!(
+            error.to_string().contains("can't return a value"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn leakproof_requires_immutable_or_stable() {
+        let func = quote! { fn example(a: i32) -> bool { a > 0 } };
+        for attr in [
+            quote! { immutable, leakproof },
+            quote! { leakproof, stable },
+        ] {
+            assert!(PgExtern::new(attr, func.clone()).is_ok());
+        }
+        for attr in [quote! { leakproof }, quote! { volatile, leakproof }] {
+            let error = PgExtern::new(attr, func.clone())
+                .err()
+                .expect("a volatile leakproof function was accepted");
+            assert!(
+                error.to_string().contains("`immutable` or `stable`"),
+                "{}",
+                error
+            );
+        }
+    }
+}
@@ -170,7 +170,7 @@ impl TryFrom<&syn::ReturnType> for Returning {
                                     _ => (),
                                 }
                             }
-                            Returning::Type(syn::Type::Path(static_ty.clone()))
+                            Returning::Type(syn::Type::Path(flatten_nested_option(static_ty)))
                         }
                     }
                     syn::Type::Reference(mut ty_ref) => {
@@ -198,6 +198,36 @@ impl TryFrom<&syn::ReturnType> for Returning {
     }
 }
 
+/// `Option<Option<T>>` is returned as SQL NULL for both `None` and `Some(None)`, so it has the
+/// same SQL type as `Option<T>`
+fn flatten_nested_option(mut ty: syn::TypePath) -> syn::TypePath {
+    fn inner_option(ty: &syn::TypePath) -> Option<syn::TypePath> {
+        let segment = ty.path.segments.last()?;
+        if segment.ident != "Option" {
+            return None;
+        }
+        match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(syn::GenericArgument::Type(syn::Type::Path(inner))) => {
+                    let inner_segment = inner.path.segments.last()?;
+                    if inner_segment.ident == "Option" {
+                        Some(inner.clone())
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    while let Some(inner) = inner_option(&ty) {
+        ty = inner;
+    }
+    ty
+}
+
 impl ToTokens for Returning {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let quoted = match self {
@@ -321,3 +351,42 @@ impl Parse for NameMacro {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Returning;
+    use quote::ToTokens;
+    use std::convert::TryFrom;
+    use syn::parse_quote;
+
+    fn returned_type(ty: syn::ReturnType) -> String {
+        match Returning::try_from(&ty).expect("could not parse return type") {
+            Returning::Type(ty) => ty.to_token_stream().to_string().replace(" ", ""),
+            other => panic!("not a plain type: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nested_option_is_flattened() {
+        assert_eq!(
+            "Option<i32>",
+            returned_type(parse_quote! { -> Option<Option<i32>> })
+        );
+        assert_eq!(
+            "Option<i32>",
+            returned_type(parse_quote! { -> Option<Option<Option<i32>>> })
+        );
+    }
+
+    #[test]
+    fn option_is_unchanged() {
+        assert_eq!(
+            "Option<i32>",
+            returned_type(parse_quote! { -> Option<i32> })
+        );
+        assert_eq!(
+            "Option<Vec<Option<i32>>>",
+            returned_type(parse_quote! { -> Option<Vec<Option<i32>>> })
+        );
+    }
+}
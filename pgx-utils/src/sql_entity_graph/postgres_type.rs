@@ -9,6 +9,10 @@ use syn::{
     DeriveInput, Generics, ItemStruct,
 };
 
+const STORAGE_VALUES: &[&str] = &["plain", "external", "extended", "main"];
+const ALIGNMENT_VALUES: &[&str] = &["int4", "double"];
+const FIXED_LENGTH_ALIGNMENT_VALUES: &[&str] = &["char", "int2", "int4", "double"];
+
 /// A parsed `#[derive(PostgresType)]` item.
 ///
 /// It should be used with [`syn::parse::Parse`] functions.
@@ -37,6 +41,9 @@ pub struct PostgresType {
     generics: Generics,
     in_fn: Ident,
     out_fn: Ident,
+    storage: Option<String>,
+    alignment: Option<String>,
+    internal_length: Option<usize>,
 }
 
 impl PostgresType {
@@ -46,9 +53,112 @@ impl PostgresType {
             name,
             in_fn,
             out_fn,
+            storage: None,
+            alignment: None,
+            internal_length: None,
         }
     }
 
+    /// The fixed size of the type, in bytes, from `#[pg_type(internallength = ...)]`, or `None` if
+    /// it's a varlena type.
+    pub fn internal_length(&self) -> Option<usize> {
+        self.internal_length
+    }
+
+    /// Apply any `#[pg_type(storage = "...", alignment = "...", internallength = ...)]` attributes.
+    pub fn with_attributes(mut self, attrs: &[syn::Attribute]) -> Result<Self, syn::Error> {
+        let mut alignment_lit = None;
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("pg_type")) {
+            let nested = match attr.parse_meta()? {
+                syn::Meta::List(list) => list.nested,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected `#[pg_type(storage = \"...\", alignment = \"...\")]`",
+                    ))
+                }
+            };
+            for meta in nested {
+                let name_value = match meta {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) => name_value,
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `storage = \"...\"`, `alignment = \"...\"` or `internallength = ...`",
+                        ))
+                    }
+                };
+                if name_value.path.is_ident("internallength") {
+                    let length = match &name_value.lit {
+                        syn::Lit::Int(length) => length.base10_parse::<usize>()?,
+                        other => return Err(syn::Error::new_spanned(other, "expected an integer")),
+                    };
+                    if length == 0 {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.lit,
+                            "`internallength` must be greater than zero",
+                        ));
+                    }
+                    self.internal_length = Some(length);
+                    continue;
+                }
+
+                let value = match &name_value.lit {
+                    syn::Lit::Str(value) => value.value(),
+                    other => return Err(syn::Error::new_spanned(other, "expected a string")),
+                };
+                if name_value.path.is_ident("storage") {
+                    if !STORAGE_VALUES.contains(&value.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.lit,
+                            format!("`storage` must be one of: {}", STORAGE_VALUES.join(", ")),
+                        ));
+                    }
+                    self.storage = Some(value);
+                } else if name_value.path.is_ident("alignment") {
+                    if !FIXED_LENGTH_ALIGNMENT_VALUES.contains(&value.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.lit,
+                            format!(
+                                "`alignment` must be one of: {}",
+                                FIXED_LENGTH_ALIGNMENT_VALUES.join(", ")
+                            ),
+                        ));
+                    }
+                    alignment_lit = Some(name_value.lit.clone());
+                    self.alignment = Some(value);
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        "unknown `pg_type` attribute, expected `storage`, `alignment` or `internallength`",
+                    ));
+                }
+            }
+        }
+
+        match (self.internal_length, &self.alignment, &self.storage) {
+            // Postgres requires varlena types be at least `int4` aligned.
+            (None, Some(alignment), _) if !ALIGNMENT_VALUES.contains(&alignment.as_str()) => {
+                return Err(syn::Error::new_spanned(
+                    alignment_lit,
+                    format!(
+                        "`alignment` of a varlena type must be one of: {}",
+                        ALIGNMENT_VALUES.join(", ")
+                    ),
+                ))
+            }
+            // And fixed-length types can't be TOASTed.
+            (Some(_), _, Some(storage)) if storage != "plain" => {
+                return Err(syn::Error::new(
+                    self.name.span(),
+                    "a type with an `internallength` must have `plain` storage",
+                ))
+            }
+            _ => (),
+        }
+        Ok(self)
+    }
+
     pub fn from_derive_input(derive_input: DeriveInput) -> Result<Self, syn::Error> {
         let _data_struct = match derive_input.data {
             syn::Data::Struct(data_struct) => data_struct,
@@ -67,12 +177,13 @@ impl PostgresType {
             &format!("{}_out", derive_input.ident).to_lowercase(),
             derive_input.ident.span(),
         );
-        Ok(Self::new(
+        Self::new(
             derive_input.ident,
             derive_input.generics,
             funcname_in,
             funcname_out,
-        ))
+        )
+        .with_attributes(&derive_input.attrs)
     }
 
     pub fn inventory_fn_name(&self) -> String {
@@ -101,12 +212,8 @@ impl Parse for PostgresType {
             &format!("{}_out", parsed.ident).to_lowercase(),
             parsed.ident.span(),
         );
-        Ok(Self::new(
-            parsed.ident,
-            parsed.generics,
-            funcname_in,
-            funcname_out,
-        ))
+        Self::new(parsed.ident, parsed.generics, funcname_in, funcname_out)
+            .with_attributes(&parsed.attrs)
     }
 }
 
@@ -121,6 +228,18 @@ impl ToTokens for PostgresType {
 
         let in_fn = &self.in_fn;
         let out_fn = &self.out_fn;
+        let storage = match &self.storage {
+            Some(storage) => quote! { Some(#storage) },
+            None => quote! { None },
+        };
+        let alignment = match &self.alignment {
+            Some(alignment) => quote! { Some(#alignment) },
+            None => quote! { None },
+        };
+        let internal_length = match &self.internal_length {
+            Some(internal_length) => quote! { Some(#internal_length) },
+            None => quote! { None },
+        };
 
         let sql_graph_entity_fn_name = syn::Ident::new(
             &format!("__pgx_internals_type_{}", self.name),
@@ -167,7 +286,10 @@ impl ToTokens for PostgresType {
                         let mut path_items: Vec<_> = out_fn.split("::").collect();
                         let _ = path_items.pop(); // Drop the one we don't want.
                         path_items.join("::")
-                    }
+                    },
+                    storage: #storage,
+                    alignment: #alignment,
+                    internal_length: #internal_length,
                 };
                 pgx::datum::sql_entity_graph::SqlGraphEntity::Type(submission)
             }
@@ -175,3 +297,63 @@ impl ToTokens for PostgresType {
         tokens.append_all(inv);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PostgresType;
+    use syn::parse_quote;
+
+    #[test]
+    fn storage_and_alignment() {
+        let parsed: PostgresType = parse_quote! {
+            #[pg_type(storage = "external", alignment = "double")]
+            struct Example {}
+        };
+        assert_eq!(parsed.storage.as_deref(), Some("external"));
+        assert_eq!(parsed.alignment.as_deref(), Some("double"));
+    }
+
+    #[test]
+    fn invalid_storage() {
+        let parsed: Result<PostgresType, _> = syn::parse_str(
+            r#"
+            #[pg_type(storage = "compressed")]
+            struct Example {}
+        "#,
+        );
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn internal_length() {
+        let parsed: PostgresType = parse_quote! {
+            #[pg_type(internallength = 32, alignment = "char")]
+            struct Example([u8; 32]);
+        };
+        assert_eq!(parsed.internal_length(), Some(32));
+        assert_eq!(parsed.alignment.as_deref(), Some("char"));
+        assert_eq!(parsed.storage, None);
+    }
+
+    #[test]
+    fn internal_length_rejects_toastable_storage() {
+        let parsed: Result<PostgresType, _> = syn::parse_str(
+            r#"
+            #[pg_type(internallength = 32, storage = "extended")]
+            struct Example([u8; 32]);
+        "#,
+        );
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn invalid_alignment() {
+        let parsed: Result<PostgresType, _> = syn::parse_str(
+            r#"
+            #[pg_type(alignment = "char")]
+            struct Example {}
+        "#,
+        );
+        assert!(parsed.is_err());
+    }
+}
@@ -47,6 +47,7 @@ bitflags! {
 }
 
 /// The various points in which a BackgroundWorker can be started by Postgres
+#[derive(Clone, Copy)]
 pub enum BgWorkerStartTime {
     PostmasterStart = pg_sys::BgWorkerStartTime_BgWorkerStart_PostmasterStart as isize,
     ConsistentState = pg_sys::BgWorkerStartTime_BgWorkerStart_ConsistentState as isize,
@@ -189,7 +190,8 @@ unsafe extern "C" fn worker_spi_sigterm(_signal_args: i32) {
 /// A builder-style interface for creating a new Background Worker
 ///
 /// This must be used from within your extension's `_PG_init()` function,
-/// finishing with the `.load()` function.
+/// finishing with the `.load()` function, or from a regular backend, finishing
+/// with the `.load_dynamic()` function.
 ///
 /// ## Example
 ///
@@ -373,8 +375,40 @@ impl BackgroundWorkerBuilder {
     /// Once properly configured, call `load()` to get the BackgroundWorker registered and
     /// started at the proper time by Postgres.
     pub fn load(self: Self) {
+        let mut bgw = self.to_pg();
+
+        unsafe {
+            pg_sys::RegisterBackgroundWorker(&mut bgw);
+            if self.bgw_flags.contains(BGWflags::BGWORKER_SHMEM_ACCESS)
+                && self.shared_memory_startup_fn.is_some()
+            {
+                PREV_SHMEM_STARTUP_HOOK = pg_sys::shmem_startup_hook;
+                pg_sys::shmem_startup_hook = self.shared_memory_startup_fn;
+            }
+        };
+    }
+
+    /// Register and start this BackgroundWorker from a regular backend, rather than from
+    /// `_PG_init()`.  This does not require the extension to be in `shared_preload_libraries`.
+    ///
+    /// Returns `None` if no background worker slots are free (see `max_worker_processes`).
+    ///
+    /// In order to wait on the returned `DynamicBackgroundWorker`, this backend must be notified
+    /// of the worker's state changes, via `.set_notify_pid(unsafe { pg_sys::MyProcPid })`.
+    pub fn load_dynamic(self: Self) -> Option<DynamicBackgroundWorker> {
+        let mut bgw = self.to_pg();
+        let mut handle = std::ptr::null_mut();
+
+        if unsafe { pg_sys::RegisterDynamicBackgroundWorker(&mut bgw, &mut handle) } {
+            Some(DynamicBackgroundWorker { handle })
+        } else {
+            None
+        }
+    }
+
+    fn to_pg(&self) -> pg_sys::BackgroundWorker {
         #[cfg(feature = "pg10")]
-        let mut bgw = pg_sys::BackgroundWorker {
+        let bgw = pg_sys::BackgroundWorker {
             bgw_name: RpgffiChar::from(&self.bgw_name[..]).0,
             bgw_flags: self.bgw_flags.bits(),
             bgw_start_time: self.bgw_start_time as u32,
@@ -390,7 +424,7 @@ impl BackgroundWorkerBuilder {
         };
 
         #[cfg(any(feature = "pg11", feature = "pg12", feature = "pg13", feature = "pg14"))]
-        let mut bgw = pg_sys::BackgroundWorker {
+        let bgw = pg_sys::BackgroundWorker {
             bgw_name: RpgffiChar::from(&self.bgw_name[..]).0,
             bgw_type: RpgffiChar::from(&self.bgw_type[..]).0,
             bgw_flags: self.bgw_flags.bits(),
@@ -406,15 +440,52 @@ impl BackgroundWorkerBuilder {
             bgw_notify_pid: self.bgw_notify_pid,
         };
 
-        unsafe {
-            pg_sys::RegisterBackgroundWorker(&mut bgw);
-            if self.bgw_flags.contains(BGWflags::BGWORKER_SHMEM_ACCESS)
-                && self.shared_memory_startup_fn.is_some()
-            {
-                PREV_SHMEM_STARTUP_HOOK = pg_sys::shmem_startup_hook;
-                pg_sys::shmem_startup_hook = self.shared_memory_startup_fn;
-            }
-        };
+        bgw
+    }
+}
+
+/// The state of a `DynamicBackgroundWorker`, as reported by Postgres
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundWorkerStatus {
+    Started,
+    NotYetStarted,
+    Stopped,
+    PostmasterDied,
+}
+
+impl From<pg_sys::BgwHandleStatus> for BackgroundWorkerStatus {
+    fn from(status: pg_sys::BgwHandleStatus) -> Self {
+        match status {
+            pg_sys::BgwHandleStatus_BGWH_STARTED => BackgroundWorkerStatus::Started,
+            pg_sys::BgwHandleStatus_BGWH_NOT_YET_STARTED => BackgroundWorkerStatus::NotYetStarted,
+            pg_sys::BgwHandleStatus_BGWH_STOPPED => BackgroundWorkerStatus::Stopped,
+            pg_sys::BgwHandleStatus_BGWH_POSTMASTER_DIED => BackgroundWorkerStatus::PostmasterDied,
+            _ => panic!("unrecognized BgwHandleStatus: {}", status),
+        }
+    }
+}
+
+/// A BackgroundWorker started by `BackgroundWorkerBuilder::load_dynamic()`
+pub struct DynamicBackgroundWorker {
+    handle: *mut pg_sys::BackgroundWorkerHandle,
+}
+
+impl DynamicBackgroundWorker {
+    /// Block until Postgres has started this BackgroundWorker, returning its pid
+    pub fn wait_for_startup(&self) -> Result<pg_sys::pid_t, BackgroundWorkerStatus> {
+        let mut pid = 0;
+        match unsafe { pg_sys::WaitForBackgroundWorkerStartup(self.handle, &mut pid) }.into() {
+            BackgroundWorkerStatus::Started => Ok(pid),
+            status => Err(status),
+        }
+    }
+
+    /// Block until this BackgroundWorker has exited
+    pub fn wait_for_shutdown(self) -> Result<(), BackgroundWorkerStatus> {
+        match unsafe { pg_sys::WaitForBackgroundWorkerShutdown(self.handle) }.into() {
+            BackgroundWorkerStatus::Stopped => Ok(()),
+            status => Err(status),
+        }
     }
 }
 
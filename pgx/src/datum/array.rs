@@ -2,21 +2,57 @@
 // governed by the MIT license that can be found in the LICENSE file.
 
 use crate::{pg_sys, void_mut_ptr, FromDatum, IntoDatum, PgMemoryContexts};
+use once_cell::unsync::OnceCell;
 use serde::Serializer;
 use std::marker::PhantomData;
 
 pub type VariadicArray<'a, T> = Array<'a, T>;
 
+/// An array element type that Postgres stores in an array's data exactly as Rust lays it out,
+/// one value after the other, so that [`Array::as_slice()`] can borrow the elements in place.
+///
+/// # Safety
+///
+/// `ELEMENT_OID` must be a fixed-length, pass-by-value Postgres type whose representation is
+/// identical to `Self`'s, in both size and alignment.
+pub unsafe trait FixedSizeArrayElement: FromDatum + Copy {
+    const ELEMENT_OID: pg_sys::Oid;
+}
+
+unsafe impl FixedSizeArrayElement for bool {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::BOOLOID;
+}
+unsafe impl FixedSizeArrayElement for i8 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::CHAROID;
+}
+unsafe impl FixedSizeArrayElement for i16 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::INT2OID;
+}
+unsafe impl FixedSizeArrayElement for i32 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::INT4OID;
+}
+unsafe impl FixedSizeArrayElement for u32 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::OIDOID;
+}
+unsafe impl FixedSizeArrayElement for i64 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::INT8OID;
+}
+unsafe impl FixedSizeArrayElement for f32 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::FLOAT4OID;
+}
+unsafe impl FixedSizeArrayElement for f64 {
+    const ELEMENT_OID: pg_sys::Oid = pg_sys::FLOAT8OID;
+}
+
 pub struct Array<'a, T: FromDatum> {
     ptr: *mut pg_sys::varlena,
     array_type: *mut pg_sys::ArrayType,
-    elements: *mut pg_sys::Datum,
-    nulls: *mut bool,
     typoid: pg_sys::Oid,
     nelems: usize,
-    elem_slice: &'a [pg_sys::Datum],
-    null_slice: &'a [bool],
-    _marker: PhantomData<T>,
+    /// The elements and their "is_null" indicators, which are only copied out of `array_type`
+    /// (with `deconstruct_array()`) once they're first needed as `Datum`s
+    deconstructed: OnceCell<(*mut pg_sys::Datum, *mut bool)>,
+    _marker: PhantomData<(&'a (), T)>,
 }
 
 impl<'a, T: FromDatum + serde::Serialize> serde::Serialize for Array<'a, T> {
@@ -52,15 +88,14 @@ impl<'a, T: FromDatum> Array<'a, T> {
         nulls: *mut bool,
         nelems: usize,
     ) -> Array<'a, T> {
+        let deconstructed = OnceCell::new();
+        let _ = deconstructed.set((elements, nulls));
         Array::<T> {
             ptr: std::ptr::null_mut(),
             array_type: std::ptr::null_mut(),
-            elements,
-            nulls,
             typoid: pg_sys::InvalidOid,
             nelems,
-            elem_slice: std::slice::from_raw_parts(elements, nelems),
-            null_slice: std::slice::from_raw_parts(nulls, nelems),
+            deconstructed,
             _marker: PhantomData,
         }
     }
@@ -68,24 +103,51 @@ impl<'a, T: FromDatum> Array<'a, T> {
     unsafe fn from_pg(
         ptr: *mut pg_sys::varlena,
         array_type: *mut pg_sys::ArrayType,
-        elements: *mut pg_sys::Datum,
-        nulls: *mut bool,
         typoid: pg_sys::Oid,
         nelems: usize,
     ) -> Self {
         Array::<T> {
             ptr,
             array_type,
-            elements,
-            nulls,
             typoid,
             nelems,
-            elem_slice: std::slice::from_raw_parts(elements, nelems),
-            null_slice: std::slice::from_raw_parts(nulls, nelems),
+            deconstructed: OnceCell::new(),
             _marker: PhantomData,
         }
     }
 
+    /// The elements and "is_null" indicators, deconstructing the array the first time
+    fn deconstructed(&self) -> (*mut pg_sys::Datum, *mut bool) {
+        *self.deconstructed.get_or_init(|| unsafe {
+            let elemtype = (*self.array_type).elemtype;
+
+            // outvals for get_typlenbyvalalign()
+            let mut typlen = 0;
+            let mut typbyval = false;
+            let mut typalign = 0;
+
+            pg_sys::get_typlenbyvalalign(elemtype, &mut typlen, &mut typbyval, &mut typalign);
+
+            // outvals for deconstruct_array()
+            let mut elements = std::ptr::null_mut();
+            let mut nulls = std::ptr::null_mut();
+            let mut nelems = 0;
+
+            pg_sys::deconstruct_array(
+                self.array_type,
+                elemtype,
+                typlen as i32,
+                typbyval,
+                typalign,
+                &mut elements,
+                &mut nulls,
+                &mut nelems,
+            );
+
+            (elements, nulls)
+        })
+    }
+
     pub fn into_array_type(self) -> *const pg_sys::ArrayType {
         if self.array_type.is_null() {
             panic!("attempt to dereference a NULL array");
@@ -96,13 +158,67 @@ impl<'a, T: FromDatum> Array<'a, T> {
         ptr
     }
 
-    pub fn as_slice(&self) -> &[T] {
-        let sizeof_type = std::mem::size_of::<T>();
-        let sizeof_datums = std::mem::size_of_val(self.elem_slice);
+    /// Borrow the elements of the array where they are, in the array's own data, without
+    /// copying them.  The slice is valid for as long as the array is.
+    ///
+    /// This function will panic when called if the array contains any SQL NULL values, or if its
+    /// elements aren't of `T`'s Postgres type.  See [`Array::try_as_slice()`].
+    ///
+    /// Before `T` was required to be a [`FixedSizeArrayElement`], this reinterpreted the array's
+    /// `Datum`s as `T`s, which was only correct for 8-byte pass-by-value types.  Code that needs
+    /// those `Datum`s, for any `T`, can use [`Array::as_datum_slice()`] instead.
+    pub fn as_slice(&self) -> &[T]
+    where
+        T: FixedSizeArrayElement,
+    {
+        self.try_as_slice().expect("array contains NULL")
+    }
+
+    /// Like [`Array::as_slice()`], but `None` if the array contains any SQL NULL values, in which
+    /// case its elements can still be had, one at a time, from [`Array::iter()`].
+    pub fn try_as_slice(&self) -> Option<&[T]>
+    where
+        T: FixedSizeArrayElement,
+    {
+        if self.array_type.is_null() {
+            panic!("array is NULL");
+        }
+
+        unsafe {
+            let array = &*self.array_type;
+            if array.elemtype != T::ELEMENT_OID {
+                panic!(
+                    "an array of type oid {} can't be borrowed as a slice of type oid {}",
+                    array.elemtype,
+                    T::ELEMENT_OID
+                );
+            } else if array.dataoffset != 0 {
+                // the array has a NULL bitmap, so at least one of its elements is NULL
+                return None;
+            }
+
+            // equivalent to Postgres' ARR_DATA_PTR() of an array without a NULL bitmap
+            let overhead = std::mem::size_of::<pg_sys::ArrayType>()
+                + 2 * std::mem::size_of::<i32>() * array.ndim as usize;
+            let overhead = (overhead + pg_sys::MAXIMUM_ALIGNOF as usize - 1)
+                & !(pg_sys::MAXIMUM_ALIGNOF as usize - 1);
+            let data = (self.array_type as *const u8).add(overhead) as *const T;
+            Some(std::slice::from_raw_parts(data, self.nelems))
+        }
+    }
+
+    /// The array's elements as `Datum`s, one per element, along with whether each is NULL,
+    /// deconstructing the array the first time.  Unlike [`Array::as_slice()`], this works for any
+    /// `T`, but copies the elements out of the array.
+    pub fn as_datum_slice(&self) -> (&[pg_sys::Datum], &[bool]) {
+        let (elements, nulls) = self.deconstructed();
+        if self.nelems == 0 {
+            return (&[], &[]);
+        }
         unsafe {
-            std::slice::from_raw_parts(
-                self.elem_slice.as_ptr() as *const T,
-                sizeof_datums / sizeof_type,
+            (
+                std::slice::from_raw_parts(elements, self.nelems),
+                std::slice::from_raw_parts(nulls, self.nelems),
             )
         }
     }
@@ -147,7 +263,8 @@ impl<'a, T: FromDatum> Array<'a, T> {
         if i >= self.nelems {
             None
         } else {
-            Some(unsafe { T::from_datum(self.elem_slice[i], self.null_slice[i], self.typoid) })
+            let (elements, nulls) = self.deconstructed();
+            Some(unsafe { T::from_datum(*elements.add(i), *nulls.add(i), self.typoid) })
         }
     }
 }
@@ -245,15 +362,17 @@ impl<'a, T: FromDatum> Iterator for ArrayIntoIterator<'a, T> {
 
 impl<'a, T: FromDatum> Drop for Array<'a, T> {
     fn drop(&mut self) {
-        if !self.elements.is_null() {
-            unsafe {
-                pg_sys::pfree(self.elements as void_mut_ptr);
+        if let Some(&(elements, nulls)) = self.deconstructed.get() {
+            if !elements.is_null() {
+                unsafe {
+                    pg_sys::pfree(elements as void_mut_ptr);
+                }
             }
-        }
 
-        if !self.nulls.is_null() {
-            unsafe {
-                pg_sys::pfree(self.nulls as void_mut_ptr);
+            if !nulls.is_null() {
+                unsafe {
+                    pg_sys::pfree(nulls as void_mut_ptr);
+                }
             }
         }
 
@@ -282,42 +401,12 @@ impl<'a, T: FromDatum> FromDatum for Array<'a, T> {
                 pg_sys::pg_detoast_datum(datum as *mut pg_sys::varlena) as *mut pg_sys::ArrayType;
             let array_ref = array.as_ref().expect("ArrayType * was NULL");
 
-            // outvals for get_typlenbyvalalign()
-            let mut typlen = 0;
-            let mut typbyval = false;
-            let mut typalign = 0;
-
-            pg_sys::get_typlenbyvalalign(
-                array_ref.elemtype,
-                &mut typlen,
-                &mut typbyval,
-                &mut typalign,
-            );
-
-            // outvals for deconstruct_array()
-            let mut elements = std::ptr::null_mut();
-            let mut nulls = std::ptr::null_mut();
-            let mut nelems = 0;
-
-            pg_sys::deconstruct_array(
-                array,
-                array_ref.elemtype,
-                typlen as i32,
-                typbyval,
-                typalign,
-                &mut elements,
-                &mut nulls,
-                &mut nelems,
-            );
+            // equivalent to Postgres' ArrayGetNItems(ARR_NDIM(array), ARR_DIMS(array)).  The
+            // elements themselves aren't deconstructed until they're needed
+            let dims = (array as *const u8).add(std::mem::size_of::<pg_sys::ArrayType>());
+            let nelems = pg_sys::ArrayGetNItems(array_ref.ndim, dims as *const i32);
 
-            Some(Array::from_pg(
-                ptr,
-                array,
-                elements,
-                nulls,
-                typoid,
-                nelems as usize,
-            ))
+            Some(Array::from_pg(ptr, array, typoid, nelems as usize))
         }
     }
 }
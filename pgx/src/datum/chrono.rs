@@ -0,0 +1,103 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+//! Conversions between `chrono` types and Postgres' `timestamp` and `timestamp with time zone`.
+//!
+//! Requires the `chrono` feature.
+use crate::{ereport, pg_sys, FromDatum, IntoDatum, PgLogLevel, PgSqlErrorCode};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Postgres stores timestamps as microseconds since 2000-01-01 00:00:00, and uses the
+/// extremes of an `i64` to represent `-infinity` and `infinity`
+const DT_NOBEGIN: i64 = i64::MIN;
+const DT_NOEND: i64 = i64::MAX;
+
+/// The first timestamp Postgres supports, at 4714-11-24 00:00:00 BC, and the one after the last,
+/// at 294277-01-01 00:00:00
+const MIN_TIMESTAMP: i64 = -211_813_488_000_000_000;
+const END_TIMESTAMP: i64 = 9_223_371_331_200_000_000;
+
+/// Raise an ERROR for a timestamp that can't be converted, like Postgres' own "timestamp out of
+/// range"
+fn out_of_range(message: &str) -> ! {
+    ereport(
+        PgLogLevel::ERROR,
+        PgSqlErrorCode::ERRCODE_DATETIME_FIELD_OVERFLOW,
+        message,
+        file!(),
+        line!(),
+        column!(),
+    );
+    unreachable!("ereport(ERROR) returned")
+}
+
+fn postgres_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("the Postgres epoch is a valid date")
+}
+
+fn from_postgres_micros(micros: i64) -> NaiveDateTime {
+    if micros == DT_NOBEGIN || micros == DT_NOEND {
+        out_of_range("infinite timestamps cannot be represented by chrono")
+    }
+
+    postgres_epoch()
+        .checked_add_signed(Duration::microseconds(micros))
+        .unwrap_or_else(|| out_of_range("timestamp out of range for chrono"))
+}
+
+fn into_postgres_micros(timestamp: NaiveDateTime) -> i64 {
+    match timestamp
+        .signed_duration_since(postgres_epoch())
+        .num_microseconds()
+    {
+        Some(micros) if (MIN_TIMESTAMP..END_TIMESTAMP).contains(&micros) => micros,
+        _ => out_of_range("timestamp out of range"),
+    }
+}
+
+impl FromDatum for NaiveDateTime {
+    #[inline]
+    unsafe fn from_datum(datum: pg_sys::Datum, is_null: bool, _typoid: u32) -> Option<Self> {
+        if is_null {
+            None
+        } else {
+            Some(from_postgres_micros(datum as i64))
+        }
+    }
+}
+
+impl IntoDatum for NaiveDateTime {
+    #[inline]
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        Some(into_postgres_micros(self) as pg_sys::Datum)
+    }
+
+    fn type_oid() -> u32 {
+        pg_sys::TIMESTAMPOID
+    }
+}
+
+/// A `timestamp with time zone` is always stored in UTC, so no time zone conversion happens here
+impl FromDatum for DateTime<Utc> {
+    #[inline]
+    unsafe fn from_datum(datum: pg_sys::Datum, is_null: bool, _typoid: u32) -> Option<Self> {
+        if is_null {
+            None
+        } else {
+            Some(Utc.from_utc_datetime(&from_postgres_micros(datum as i64)))
+        }
+    }
+}
+
+impl IntoDatum for DateTime<Utc> {
+    #[inline]
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        Some(into_postgres_micros(self.naive_utc()) as pg_sys::Datum)
+    }
+
+    fn type_oid() -> u32 {
+        pg_sys::TIMESTAMPTZOID
+    }
+}
@@ -0,0 +1,492 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+//! `date`, `time`, `timestamp`, and `interval` types that hold exactly what Postgres stores for
+//! each, without depending on the `time` or `chrono` crates.
+//!
+//! They're named `CivilDate`, `CivilTime`, and `CivilTimestamp` so they don't clash with
+//! [`pgx::Date`](crate::Date) and friends, and aren't re-exported at the top of `pgx`, but are
+//! used as `pgx::datetime::CivilDate`, etc:
+//!
+//! ```rust,ignore
+//! use pgx::*;
+//!
+//! #[pg_extern]
+//! fn first_of_month(date: datetime::CivilDate) -> datetime::CivilDate {
+//!     datetime::CivilDate::new(date.year(), date.month(), 1).unwrap()
+//! }
+//! ```
+use crate::{pg_sys, FromDatum, IntoDatum, PgBox};
+
+const USECS_PER_SEC: i64 = 1_000_000;
+const USECS_PER_MINUTE: i64 = 60 * USECS_PER_SEC;
+const USECS_PER_HOUR: i64 = 60 * USECS_PER_MINUTE;
+const USECS_PER_DAY: i64 = 24 * USECS_PER_HOUR;
+
+/// The Julian day of 2000-01-01, from which Postgres counts dates and timestamps
+const POSTGRES_EPOCH_JDATE: i64 = pg_sys::POSTGRES_EPOCH_JDATE as i64;
+
+/// The Julian day of 4714-11-24 BC, the first Postgres supports, and of 5874898-01-01, the day
+/// after the last date
+const DATETIME_MIN_JULIAN: i64 = 0;
+const DATE_END_JULIAN: i64 = 2_147_483_494;
+
+/// The first timestamp Postgres supports, at 4714-11-24 00:00:00 BC, and the one after the last,
+/// at 294277-01-01 00:00:00
+const MIN_TIMESTAMP: i64 = -211_813_488_000_000_000;
+const END_TIMESTAMP: i64 = 9_223_371_331_200_000_000;
+
+/// The Julian day of a year (in which 1 BC is year 0), month, and day of the month, as Postgres'
+/// `date2j()` computes it
+fn date2j(year: i32, month: u8, day: u8) -> i64 {
+    let (y, m) = if month > 2 {
+        (year as i64 + 4800, month as i64 + 1)
+    } else {
+        (year as i64 + 4799, month as i64 + 13)
+    };
+    let century = y / 100;
+    y * 365 - 32167 + y / 4 - century + century / 4 + 7834 * m / 256 + day as i64
+}
+
+/// The year (in which 1 BC is year 0), month, and day of the month of a Julian day, as Postgres'
+/// `j2date()` computes it
+fn j2date(julian_day: i64) -> (i32, u8, u8) {
+    let mut julian = julian_day + 32044;
+    let mut quad = julian / 146097;
+    let extra = (julian - quad * 146097) * 4 + 3;
+    julian += 60 + quad * 3 + extra / 146097;
+    quad = julian / 1461;
+    julian -= quad * 1461;
+    let mut y = julian * 4 / 1461;
+    julian = if y != 0 {
+        (julian + 305) % 365
+    } else {
+        (julian + 306) % 366
+    } + 123;
+    y += quad * 4;
+    let quad = julian * 2141 / 65536;
+    let day = julian - 7834 * quad / 256;
+    let month = (quad + 10) % 12 + 1;
+    ((y - 4800) as i32, month as u8, day as u8)
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// A `date`, stored as the number of days since 2000-01-01
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CivilDate(i32);
+
+impl CivilDate {
+    /// `-infinity`, which is before every other date
+    pub const NEG_INFINITY: CivilDate = CivilDate(i32::MIN);
+    /// `infinity`, which is after every other date
+    pub const INFINITY: CivilDate = CivilDate(i32::MAX);
+
+    /// The date of a `year`, `month` (from 1 to 12), and `day` of the month, or `None` if there's
+    /// no such date or it's outside of the range Postgres supports
+    ///
+    /// Like Postgres' `EXTRACT(YEAR FROM ...)`, there's no year 0, and 1 BC is year -1.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if year == 0 || !(1..=12).contains(&month) {
+            return None;
+        }
+        let year = if year < 0 { year + 1 } else { year };
+        let days_in_month = match month {
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        if day < 1 || day > days_in_month {
+            return None;
+        }
+
+        let julian_day = date2j(year, month, day);
+        if !(DATETIME_MIN_JULIAN..DATE_END_JULIAN).contains(&julian_day) {
+            return None;
+        }
+        Some(CivilDate((julian_day - POSTGRES_EPOCH_JDATE) as i32))
+    }
+
+    /// The date this many days after 2000-01-01, or before it if negative
+    pub fn from_pg_epoch_days(days: i32) -> Self {
+        CivilDate(days)
+    }
+
+    /// The number of days since 2000-01-01, which is how Postgres stores the date
+    pub fn to_pg_epoch_days(self) -> i32 {
+        self.0
+    }
+
+    pub fn is_infinite(self) -> bool {
+        self == CivilDate::NEG_INFINITY || self == CivilDate::INFINITY
+    }
+
+    fn julian_day(self) -> i64 {
+        if self.is_infinite() {
+            panic!("date is infinite");
+        }
+        self.0 as i64 + POSTGRES_EPOCH_JDATE
+    }
+
+    /// The year, where 1 BC is -1, like `EXTRACT(YEAR FROM ...)`.  Panics if the date is infinite.
+    pub fn year(self) -> i32 {
+        match j2date(self.julian_day()).0 {
+            year if year <= 0 => year - 1,
+            year => year,
+        }
+    }
+
+    /// The month, from 1 to 12.  Panics if the date is infinite.
+    pub fn month(self) -> u8 {
+        j2date(self.julian_day()).1
+    }
+
+    /// The day of the month, from 1 to 31.  Panics if the date is infinite.
+    pub fn day(self) -> u8 {
+        j2date(self.julian_day()).2
+    }
+
+    /// The day of the week, from 0 for Sunday to 6 for Saturday, like `EXTRACT(DOW FROM ...)`.
+    /// Panics if the date is infinite.
+    pub fn day_of_week(self) -> u8 {
+        ((self.julian_day() + 1) % 7) as u8
+    }
+
+    /// The day of the year, from 1 to 366, like `EXTRACT(DOY FROM ...)`.  Panics if the date is
+    /// infinite.
+    pub fn day_of_year(self) -> u16 {
+        let julian_day = self.julian_day();
+        let (year, _, _) = j2date(julian_day);
+        (julian_day - date2j(year, 1, 1) + 1) as u16
+    }
+}
+
+impl FromDatum for CivilDate {
+    const NEEDS_TYPID: bool = false;
+
+    #[inline]
+    unsafe fn from_datum(
+        datum: pg_sys::Datum,
+        is_null: bool,
+        _typoid: pg_sys::Oid,
+    ) -> Option<Self> {
+        if is_null {
+            None
+        } else {
+            Some(CivilDate(datum as i32))
+        }
+    }
+}
+
+impl IntoDatum for CivilDate {
+    #[inline]
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        Some(self.0 as pg_sys::Datum)
+    }
+
+    fn type_oid() -> pg_sys::Oid {
+        pg_sys::DATEOID
+    }
+}
+
+/// A `time` (without time zone), stored as the number of microseconds since midnight
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CivilTime(i64);
+
+impl CivilTime {
+    /// The time of an `hour` (from 0 to 23), `minute`, `second`, and `microsecond`, or `None` if
+    /// any is out of range.  Like Postgres, `24:00:00` is allowed too, as the end of the day.
+    pub fn new(hour: u8, minute: u8, second: u8, microsecond: u32) -> Option<Self> {
+        let micros = hour as i64 * USECS_PER_HOUR
+            + minute as i64 * USECS_PER_MINUTE
+            + second as i64 * USECS_PER_SEC
+            + microsecond as i64;
+        if minute >= 60
+            || second >= 60
+            || microsecond as i64 >= USECS_PER_SEC
+            || micros > USECS_PER_DAY
+        {
+            None
+        } else {
+            Some(CivilTime(micros))
+        }
+    }
+
+    /// The time this many microseconds after midnight, or `None` if it's more than a day
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        if (0..=USECS_PER_DAY).contains(&micros) {
+            Some(CivilTime(micros))
+        } else {
+            None
+        }
+    }
+
+    /// The number of microseconds since midnight, which is how Postgres stores the time
+    pub fn to_micros(self) -> i64 {
+        self.0
+    }
+
+    /// The hour, from 0 to 24
+    pub fn hour(self) -> u8 {
+        (self.0 / USECS_PER_HOUR) as u8
+    }
+
+    pub fn minute(self) -> u8 {
+        (self.0 % USECS_PER_HOUR / USECS_PER_MINUTE) as u8
+    }
+
+    pub fn second(self) -> u8 {
+        (self.0 % USECS_PER_MINUTE / USECS_PER_SEC) as u8
+    }
+
+    pub fn microsecond(self) -> u32 {
+        (self.0 % USECS_PER_SEC) as u32
+    }
+}
+
+impl FromDatum for CivilTime {
+    const NEEDS_TYPID: bool = false;
+
+    #[inline]
+    unsafe fn from_datum(
+        datum: pg_sys::Datum,
+        is_null: bool,
+        _typoid: pg_sys::Oid,
+    ) -> Option<Self> {
+        if is_null {
+            None
+        } else {
+            Some(CivilTime(datum as i64))
+        }
+    }
+}
+
+impl IntoDatum for CivilTime {
+    #[inline]
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        Some(self.0 as pg_sys::Datum)
+    }
+
+    fn type_oid() -> pg_sys::Oid {
+        pg_sys::TIMEOID
+    }
+}
+
+/// A `timestamp` (without time zone), stored as the number of microseconds since
+/// 2000-01-01 00:00:00
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CivilTimestamp(i64);
+
+impl CivilTimestamp {
+    /// `-infinity`, which is before every other timestamp
+    pub const NEG_INFINITY: CivilTimestamp = CivilTimestamp(i64::MIN);
+    /// `infinity`, which is after every other timestamp
+    pub const INFINITY: CivilTimestamp = CivilTimestamp(i64::MAX);
+
+    /// The timestamp at `time` on `date`, or `None` if it's outside of the range Postgres
+    /// supports, as is an infinite `date`
+    pub fn new(date: CivilDate, time: CivilTime) -> Option<Self> {
+        if date.is_infinite() {
+            return None;
+        }
+        CivilTimestamp::from_pg_epoch_micros(date.0 as i64 * USECS_PER_DAY + time.0)
+    }
+
+    /// The timestamp this many microseconds after 2000-01-01 00:00:00, or before it if negative,
+    /// or `None` if it's outside of the range Postgres supports
+    pub fn from_pg_epoch_micros(micros: i64) -> Option<Self> {
+        if (MIN_TIMESTAMP..END_TIMESTAMP).contains(&micros) {
+            Some(CivilTimestamp(micros))
+        } else {
+            None
+        }
+    }
+
+    /// The number of microseconds since 2000-01-01 00:00:00, which is how Postgres stores the
+    /// timestamp
+    pub fn to_pg_epoch_micros(self) -> i64 {
+        self.0
+    }
+
+    pub fn is_infinite(self) -> bool {
+        self == CivilTimestamp::NEG_INFINITY || self == CivilTimestamp::INFINITY
+    }
+
+    /// The date of the timestamp.  Panics if the timestamp is infinite.
+    pub fn date(self) -> CivilDate {
+        if self.is_infinite() {
+            panic!("timestamp is infinite");
+        }
+        CivilDate(self.0.div_euclid(USECS_PER_DAY) as i32)
+    }
+
+    /// The time of day of the timestamp.  Panics if the timestamp is infinite.
+    pub fn time(self) -> CivilTime {
+        if self.is_infinite() {
+            panic!("timestamp is infinite");
+        }
+        CivilTime(self.0.rem_euclid(USECS_PER_DAY))
+    }
+}
+
+impl FromDatum for CivilTimestamp {
+    const NEEDS_TYPID: bool = false;
+
+    #[inline]
+    unsafe fn from_datum(
+        datum: pg_sys::Datum,
+        is_null: bool,
+        _typoid: pg_sys::Oid,
+    ) -> Option<Self> {
+        if is_null {
+            None
+        } else {
+            Some(CivilTimestamp(datum as i64))
+        }
+    }
+}
+
+impl IntoDatum for CivilTimestamp {
+    #[inline]
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        Some(self.0 as pg_sys::Datum)
+    }
+
+    fn type_oid() -> pg_sys::Oid {
+        pg_sys::TIMESTAMPOID
+    }
+}
+
+/// An `interval`, stored as Postgres does, as separate numbers of months, days, and
+/// microseconds, since neither a month nor a day (across a daylight saving time change) has a
+/// fixed length
+///
+/// The fields `EXTRACT()` gives for an interval follow from these: its `YEAR` is
+/// `months() / 12`, its `MONTH` is `months() % 12`, and its `HOUR` is `micros() / 3_600_000_000`,
+/// for example.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Interval {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    pub fn months(self) -> i32 {
+        self.months
+    }
+
+    pub fn days(self) -> i32 {
+        self.days
+    }
+
+    pub fn micros(self) -> i64 {
+        self.micros
+    }
+}
+
+impl FromDatum for Interval {
+    const NEEDS_TYPID: bool = false;
+
+    #[inline]
+    unsafe fn from_datum(
+        datum: pg_sys::Datum,
+        is_null: bool,
+        _typoid: pg_sys::Oid,
+    ) -> Option<Self> {
+        if is_null {
+            None
+        } else if datum == 0 {
+            panic!("interval datum declared not null, but datum is zero")
+        } else {
+            let interval = (datum as *const pg_sys::Interval).read();
+            Some(Interval {
+                months: interval.month,
+                days: interval.day,
+                micros: interval.time,
+            })
+        }
+    }
+}
+
+impl IntoDatum for Interval {
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        let mut interval = PgBox::<pg_sys::Interval>::alloc();
+        interval.month = self.months;
+        interval.day = self.days;
+        interval.time = self.micros;
+        Some(interval.into_pg() as pg_sys::Datum)
+    }
+
+    fn type_oid() -> pg_sys::Oid {
+        pg_sys::INTERVALOID
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{date2j, j2date, CivilDate, CivilTime, CivilTimestamp};
+
+    #[test]
+    fn julian_days_round_trip() {
+        for (year, month, day) in [(2000, 1, 1), (1999, 12, 31), (2020, 2, 29), (-4713, 11, 24)] {
+            assert_eq!(j2date(date2j(year, month, day)), (year, month, day));
+        }
+        assert_eq!(date2j(2000, 1, 1), 2451545);
+        assert_eq!(date2j(-4713, 11, 24), 0);
+    }
+
+    #[test]
+    fn date_accessors() {
+        let date = CivilDate::new(2021, 3, 4).unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2021, 3, 4));
+        assert_eq!(date.day_of_week(), 4);
+        assert_eq!(date.day_of_year(), 63);
+        assert_eq!(CivilDate::new(2000, 1, 1).unwrap().to_pg_epoch_days(), 0);
+        assert_eq!(CivilDate::new(1999, 12, 31).unwrap().to_pg_epoch_days(), -1);
+
+        let bc = CivilDate::new(-44, 3, 15).unwrap();
+        assert_eq!((bc.year(), bc.month(), bc.day()), (-44, 3, 15));
+
+        assert_eq!(CivilDate::new(2021, 2, 29), None);
+        assert_eq!(CivilDate::new(2021, 13, 1), None);
+        assert_eq!(CivilDate::new(0, 1, 1), None);
+        assert_eq!(CivilDate::new(-4714, 11, 23), None);
+        assert!(CivilDate::new(2020, 2, 29).is_some());
+    }
+
+    #[test]
+    fn time_and_timestamp_accessors() {
+        let time = CivilTime::new(12, 34, 56, 789).unwrap();
+        assert_eq!(
+            (
+                time.hour(),
+                time.minute(),
+                time.second(),
+                time.microsecond()
+            ),
+            (12, 34, 56, 789)
+        );
+        assert!(CivilTime::new(24, 0, 0, 0).is_some());
+        assert_eq!(CivilTime::new(24, 0, 0, 1), None);
+        assert_eq!(CivilTime::new(1, 60, 0, 0), None);
+
+        let date = CivilDate::new(1999, 12, 31).unwrap();
+        let timestamp = CivilTimestamp::new(date, time).unwrap();
+        assert_eq!(timestamp.date(), date);
+        assert_eq!(timestamp.time(), time);
+        assert!(timestamp.to_pg_epoch_micros() < 0);
+        assert_eq!(CivilTimestamp::new(CivilDate::INFINITY, time), None);
+    }
+}
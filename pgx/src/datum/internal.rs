@@ -34,6 +34,19 @@ impl Internal {
         ))
     }
 
+    /// Construct a new Internal from any type, allocated in the specified memory context.
+    ///
+    /// The value will be dropped when that context is reset or deleted.  An aggregate's `internal`
+    /// transition state must outlive the call that creates it, so it belongs in the aggregate's
+    /// memory context (see [`pg_agg_context`](crate::pg_agg_context)) rather than the
+    /// [PgMemoryContexts::CurrentMemoryContext], which is reset after each row.
+    #[inline]
+    pub fn new_in<T>(mut memory_context: PgMemoryContexts, t: T) -> Self {
+        Self(Some(
+            memory_context.leak_and_drop_on_delete(t) as pg_sys::Datum
+        ))
+    }
+
     /// Return a reference to the memory pointed to by this [Internal], as `Some(&T)`, unless the
     /// backing datum is null, then `None`.
     ///
@@ -29,6 +29,10 @@ pub trait IntoDatum {
 }
 
 /// for supporting NULL as the None value of an Option<T>
+///
+/// A nested `Option<Option<T>>` is NULL for both `None` and `Some(None)`.  A scalar SQL value is
+/// either NULL or it isn't, so there's no way to tell the two apart once converted -- only the
+/// members of an array or composite type can be "present but NULL".
 impl<T> IntoDatum for Option<T>
 where
     T: IntoDatum,
@@ -6,7 +6,10 @@
 mod anyarray;
 mod anyelement;
 mod array;
+#[cfg(feature = "chrono")]
+mod chrono;
 mod date;
+pub mod datetime;
 mod from;
 mod geo;
 mod inet;
@@ -22,6 +25,7 @@ mod time_stamp_with_timezone;
 mod time_with_timezone;
 mod tuples;
 mod uuid;
+mod varchar;
 mod varlena;
 
 pub use self::time::*;
@@ -45,6 +49,7 @@ pub use time_stamp::*;
 pub use time_stamp_with_timezone::*;
 pub use time_with_timezone::*;
 pub use tuples::*;
+pub use varchar::*;
 pub use varlena::*;
 
 /// A tagging trait to indicate a user type is also meant to be used by Postgres
@@ -1,6 +1,10 @@
 use super::{SqlGraphEntity, SqlGraphIdentifier, ToSql};
 use core::convert::TryFrom;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tracing_error::SpanTrace;
 
 /// The parsed contents of a `.control` file.
@@ -22,6 +26,10 @@ pub struct ControlFile {
     pub relocatable: bool,
     pub superuser: bool,
     pub schema: Option<String>,
+    /// The extension's name, which is the file name of the `.control` file without its extension.
+    ///
+    /// This isn't a field of the file itself, so it's `None` when parsed from a string.
+    pub name: Option<String>,
 }
 
 impl ControlFile {
@@ -39,13 +47,15 @@ impl ControlFile {
     pub fn from_str(input: &str) -> Result<Self, ControlFileError> {
         let mut temp = HashMap::new();
         for line in input.lines() {
-            let parts: Vec<&str> = line.split('=').collect();
-
-            if parts.len() != 2 {
-                continue;
-            }
+            // comments, which may well be commented-out settings, or follow a value
+            let line = strip_comment(line);
 
-            let (k, v) = (parts.get(0).unwrap().trim(), parts.get(1).unwrap().trim());
+            // only lines with exactly one `=` are settings
+            let mut parts = line.split('=');
+            let (k, v) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(k), Some(v), None) => (k.trim(), v.trim()),
+                _ => continue,
+            };
 
             let v = v.trim_start_matches('\'');
             let v = v.trim_end_matches('\'');
@@ -89,6 +99,255 @@ impl ControlFile {
                 })?
                 == &"true",
             schema: temp.get("schema").map(|v| v.to_string()),
+            name: None,
+        })
+    }
+}
+
+/// `line` up to any `#` comment, which doesn't start within a quoted value.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+impl ControlFile {
+    /// Find the `.control` file in `dir`, along with the versioned SQL scripts beside it.
+    ///
+    /// Only scripts named for the extension (`{name}--{version}.sql` and
+    /// `{name}--{from}--{to}.sql`) are returned, sorted by path.  The directory must contain
+    /// exactly one `.control` file.
+    ///
+    /// ```rust
+    /// use pgx::datum::sql_entity_graph::ControlFile;
+    /// use std::path::Path;
+    /// # fn main() -> eyre::Result<()> {
+    /// let dir = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/versioned"));
+    /// let (_control_file, scripts) = ControlFile::discover(dir)?;
+    /// assert_eq!(scripts.len(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(level = "info")]
+    pub fn discover(dir: &Path) -> Result<(Self, Vec<VersionScript>), ControlFileError> {
+        let io_error = |error| ControlFileError::Io {
+            path: dir.to_path_buf(),
+            error: Arc::new(error),
+            context: SpanTrace::capture(),
+        };
+
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            paths.push(entry.map_err(io_error)?.path());
+        }
+        paths.sort();
+
+        let control_paths = paths
+            .iter()
+            .filter(|path| path.extension().map_or(false, |ext| ext == "control"))
+            .collect::<Vec<_>>();
+        let control_path = match control_paths.as_slice() {
+            [control_path] => *control_path,
+            _ => {
+                return Err(ControlFileError::Discovery {
+                    dir: dir.to_path_buf(),
+                    found: control_paths.into_iter().cloned().collect(),
+                    context: SpanTrace::capture(),
+                })
+            }
+        };
+        let control_file = ControlFile::try_from(control_path.as_path())?;
+
+        let extension_name = control_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("control file name was not valid UTF-8");
+        let scripts = paths
+            .iter()
+            .filter_map(|path| VersionScript::from_path(extension_name, path))
+            .collect();
+
+        Ok((control_file, scripts))
+    }
+
+    /// List each field that differs between `old` and `self`, in the order they're declared.
+    ///
+    /// ```rust
+    /// use pgx::datum::sql_entity_graph::{ControlFieldChange, ControlFile};
+    /// # fn main() -> eyre::Result<()> {
+    /// let context = include_str!("../../../../pgx-examples/custom_types/custom_types.control");
+    /// let old = ControlFile::from_str(context)?;
+    /// let new = ControlFile {
+    ///     default_version: String::from("2.0"),
+    ///     ..old.clone()
+    /// };
+    /// assert!(new.changes_from(&old).iter().all(|change| !change.breaks_upgrades()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn changes_from(&self, old: &ControlFile) -> Vec<ControlFieldChange> {
+        let mut changes = Vec::new();
+        if old.comment != self.comment {
+            changes.push(ControlFieldChange::Comment {
+                old: old.comment.clone(),
+                new: self.comment.clone(),
+            });
+        }
+        if old.default_version != self.default_version {
+            changes.push(ControlFieldChange::DefaultVersion {
+                old: old.default_version.clone(),
+                new: self.default_version.clone(),
+            });
+        }
+        if old.module_pathname != self.module_pathname {
+            changes.push(ControlFieldChange::ModulePathname {
+                old: old.module_pathname.clone(),
+                new: self.module_pathname.clone(),
+            });
+        }
+        if old.relocatable != self.relocatable {
+            changes.push(ControlFieldChange::Relocatable {
+                old: old.relocatable,
+                new: self.relocatable,
+            });
+        }
+        if old.superuser != self.superuser {
+            changes.push(ControlFieldChange::Superuser {
+                old: old.superuser,
+                new: self.superuser,
+            });
+        }
+        if old.schema != self.schema {
+            changes.push(ControlFieldChange::Schema {
+                old: old.schema.clone(),
+                new: self.schema.clone(),
+            });
+        }
+        changes
+    }
+}
+
+/// A field that differs between two [`ControlFile`]s, from [`ControlFile::changes_from`].
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum ControlFieldChange {
+    Comment {
+        old: String,
+        new: String,
+    },
+    DefaultVersion {
+        old: String,
+        new: String,
+    },
+    ModulePathname {
+        old: String,
+        new: String,
+    },
+    Relocatable {
+        old: bool,
+        new: bool,
+    },
+    Superuser {
+        old: bool,
+        new: bool,
+    },
+    Schema {
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+impl ControlFieldChange {
+    /// The name of the field in the `.control` file.
+    pub fn field(&self) -> &'static str {
+        match self {
+            ControlFieldChange::Comment { .. } => "comment",
+            ControlFieldChange::DefaultVersion { .. } => "default_version",
+            ControlFieldChange::ModulePathname { .. } => "module_pathname",
+            ControlFieldChange::Relocatable { .. } => "relocatable",
+            ControlFieldChange::Superuser { .. } => "superuser",
+            ControlFieldChange::Schema { .. } => "schema",
+        }
+    }
+
+    /// Whether an extension already installed under the old control file can be left in a state
+    /// `ALTER EXTENSION ... UPDATE` can't reconcile with the new one.
+    ///
+    /// Installed objects stay in the schema they were created in, and who may update the
+    /// extension depends on `superuser`, so changes to `relocatable`, `superuser`, and `schema`
+    /// are the ones to check before releasing.
+    pub fn breaks_upgrades(&self) -> bool {
+        match self {
+            ControlFieldChange::Comment { .. }
+            | ControlFieldChange::DefaultVersion { .. }
+            | ControlFieldChange::ModulePathname { .. } => false,
+            ControlFieldChange::Relocatable { .. }
+            | ControlFieldChange::Superuser { .. }
+            | ControlFieldChange::Schema { .. } => true,
+        }
+    }
+}
+
+impl std::fmt::Display for ControlFieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlFieldChange::Comment { old, new }
+            | ControlFieldChange::DefaultVersion { old, new }
+            | ControlFieldChange::ModulePathname { old, new } => {
+                write!(f, "`{}` changed from `{}` to `{}`", self.field(), old, new)
+            }
+            ControlFieldChange::Relocatable { old, new }
+            | ControlFieldChange::Superuser { old, new } => {
+                write!(f, "`{}` changed from `{}` to `{}`", self.field(), old, new)
+            }
+            ControlFieldChange::Schema { old, new } => write!(
+                f,
+                "`{}` changed from `{}` to `{}`",
+                self.field(),
+                old.as_deref().unwrap_or("(none)"),
+                new.as_deref().unwrap_or("(none)")
+            ),
+        }
+    }
+}
+
+/// A versioned SQL script shipped alongside a `.control` file.
+#[derive(Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct VersionScript {
+    pub path: PathBuf,
+    /// The version this script upgrades from, or `None` if it installs `to_version` directly.
+    pub from_version: Option<String>,
+    pub to_version: String,
+}
+
+impl VersionScript {
+    /// Parse the versions out of a `{extension_name}--{version}.sql` or
+    /// `{extension_name}--{from}--{to}.sql` filename, returning `None` for anything else.
+    fn from_path(extension_name: &str, path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        let versions = file_name
+            .strip_prefix(extension_name)?
+            .strip_prefix("--")?
+            .strip_suffix(".sql")?;
+
+        let (from_version, to_version) = match versions.split("--").collect::<Vec<_>>()[..] {
+            [to] => (None, to),
+            [from, to] => (Some(from.to_string()), to),
+            _ => return None,
+        };
+        if to_version.is_empty() || from_version.as_deref() == Some("") {
+            return None;
+        }
+
+        Some(VersionScript {
+            path: path.to_path_buf(),
+            from_version,
+            to_version: to_version.to_string(),
         })
     }
 }
@@ -106,6 +365,71 @@ pub enum ControlFileError {
         field: &'static str,
         context: SpanTrace,
     },
+    Io {
+        path: PathBuf,
+        error: Arc<std::io::Error>,
+        context: SpanTrace,
+    },
+    Encoding {
+        error: std::str::Utf8Error,
+        context: SpanTrace,
+    },
+    Discovery {
+        dir: PathBuf,
+        found: Vec<PathBuf>,
+        context: SpanTrace,
+    },
+}
+
+impl ControlFileError {
+    /// The name of the field that was missing from the `.control` file, if that's what went wrong.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            ControlFileError::MissingField { field, .. } => Some(field),
+            ControlFileError::Io { .. }
+            | ControlFileError::Encoding { .. }
+            | ControlFileError::Discovery { .. } => None,
+        }
+    }
+}
+
+/// Errors are equal when they're the same kind of error about the same thing.  Where each was
+/// captured (the [`SpanTrace`]) is ignored, as is the detail of an I/O error beyond its
+/// [`std::io::ErrorKind`].
+impl PartialEq for ControlFileError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                ControlFileError::MissingField { field, .. },
+                ControlFileError::MissingField {
+                    field: other_field, ..
+                },
+            ) => field == other_field,
+            (
+                ControlFileError::Io { path, error, .. },
+                ControlFileError::Io {
+                    path: other_path,
+                    error: other_error,
+                    ..
+                },
+            ) => path == other_path && error.kind() == other_error.kind(),
+            (
+                ControlFileError::Encoding { error, .. },
+                ControlFileError::Encoding {
+                    error: other_error, ..
+                },
+            ) => error == other_error,
+            (
+                ControlFileError::Discovery { dir, found, .. },
+                ControlFileError::Discovery {
+                    dir: other_dir,
+                    found: other_found,
+                    ..
+                },
+            ) => dir == other_dir && found == other_found,
+            _ => false,
+        }
+    }
 }
 
 impl std::fmt::Display for ControlFileError {
@@ -115,12 +439,51 @@ impl std::fmt::Display for ControlFileError {
                 write!(f, "Missing field in control file! Please add `{}`.", field)?;
                 context.fmt(f)?;
             }
+            ControlFileError::Io {
+                path,
+                error,
+                context,
+            } => {
+                write!(
+                    f,
+                    "Could not read control file `{}`: {}",
+                    path.display(),
+                    error
+                )?;
+                context.fmt(f)?;
+            }
+            ControlFileError::Encoding { error, context } => {
+                write!(f, "Control file is not valid UTF-8: {}", error)?;
+                context.fmt(f)?;
+            }
+            ControlFileError::Discovery {
+                dir,
+                found,
+                context,
+            } => {
+                write!(
+                    f,
+                    "Expected exactly one control file in `{}`, found {}.",
+                    dir.display(),
+                    found.len()
+                )?;
+                context.fmt(f)?;
+            }
         };
         Ok(())
     }
 }
 
-impl std::error::Error for ControlFileError {}
+impl std::error::Error for ControlFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ControlFileError::MissingField { .. } => None,
+            ControlFileError::Io { error, .. } => Some(error.as_ref()),
+            ControlFileError::Encoding { error, .. } => Some(error),
+            ControlFileError::Discovery { .. } => None,
+        }
+    }
+}
 
 impl TryFrom<&str> for ControlFile {
     type Error = ControlFileError;
@@ -130,20 +493,91 @@ impl TryFrom<&str> for ControlFile {
     }
 }
 
+impl TryFrom<&[u8]> for ControlFile {
+    type Error = ControlFileError;
+
+    fn try_from(input: &[u8]) -> Result<Self, Self::Error> {
+        let input = std::str::from_utf8(input).map_err(|error| ControlFileError::Encoding {
+            error,
+            context: SpanTrace::capture(),
+        })?;
+        Self::from_str(input)
+    }
+}
+
+impl TryFrom<&Path> for ControlFile {
+    type Error = ControlFileError;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let input = std::fs::read(path).map_err(|error| ControlFileError::Io {
+            path: path.to_path_buf(),
+            error: Arc::new(error),
+            context: SpanTrace::capture(),
+        })?;
+        let mut control_file = Self::try_from(input.as_slice())?;
+        control_file.name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(|stem| stem.to_string());
+        Ok(control_file)
+    }
+}
+
 impl ToSql for ControlFile {
+    /// The header of the generated SQL and, if the extension's name is known, a
+    /// `COMMENT ON EXTENSION` with the `.control` file's `comment`.
+    ///
+    /// `CREATE EXTENSION` already sets that comment, but `ALTER EXTENSION ... UPDATE` doesn't, so
+    /// this keeps it current across upgrades.
     #[tracing::instrument(level = "debug", err, skip(self, _context))]
     fn to_sql(&self, _context: &super::PgxSql) -> eyre::Result<String> {
-        let sql = format!(
+        Ok(self.sql_with_ordering(
+            "The ordering of items is not stable, it is driven by a dependency graph.",
+        ))
+    }
+}
+
+impl ControlFile {
+    /// Like its [`ToSql::to_sql`], but for [`PgxSql::to_sql_pretty`](super::PgxSql::to_sql_pretty),
+    /// whose ordering is stable.
+    pub fn to_sql_pretty(&self) -> String {
+        self.sql_with_ordering(
+            "Items are grouped by kind, and ordered by their dependencies and then by where they're\n\
+             declared, so the ordering is stable.",
+        )
+    }
+
+    /// The header, describing how items are ordered with `ordering`, and the comment
+    fn sql_with_ordering(&self, ordering: &str) -> String {
+        let mut sql = format!(
             "\
             /* \n\
             This file is auto generated by pgx.\n\
             \n\
-            The ordering of items is not stable, it is driven by a dependency graph.\n\
+            {ordering}\n\
             */\
-        "
+        ",
+            ordering = ordering
         );
+        if let Some(name) = &self.name {
+            sql.push_str(&format!(
+                "\n\nCOMMENT ON EXTENSION \"{}\" IS {};",
+                name.replace('"', "\"\""),
+                quote_literal(&self.comment)
+            ));
+        }
         tracing::debug!(%sql);
-        Ok(sql)
+        sql
+    }
+}
+
+/// Quote `value` as a SQL string literal, the same way as Postgres' `quote_literal()`.
+fn quote_literal(value: &str) -> String {
+    let quoted = value.replace('\'', "''");
+    if quoted.contains('\\') {
+        format!("E'{}'", quoted.replace('\\', "\\\\"))
+    } else {
+        format!("'{}'", quoted)
     }
 }
 
@@ -163,3 +597,285 @@ impl SqlGraphIdentifier for ControlFile {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ControlFieldChange, ControlFile, ControlFileError, VersionScript};
+    use std::{convert::TryFrom, path::Path};
+    use tracing_error::SpanTrace;
+
+    #[test]
+    fn try_from_path() {
+        let path = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../pgx-examples/custom_types/custom_types.control"
+        ));
+        let control_file = ControlFile::try_from(path).expect("failed to parse control file");
+        assert_eq!(control_file.module_pathname, "$libdir/custom_types");
+        assert_eq!(control_file.name.as_deref(), Some("custom_types"));
+    }
+
+    #[test]
+    fn try_from_nonexistent_path() {
+        let path = Path::new("/this/path/does/not/exist.control");
+        match ControlFile::try_from(path) {
+            Err(ControlFileError::Io { path: errored, .. }) => assert_eq!(errored, path),
+            other => panic!("expected an Io error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn try_from_invalid_utf8() {
+        let input: &[u8] = b"comment = '\xff\xfe'\n";
+        match ControlFile::try_from(input) {
+            Err(ControlFileError::Encoding { .. }) => (),
+            other => panic!("expected an Encoding error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn quote_literal() {
+        assert_eq!(super::quote_literal("pretty"), "'pretty'");
+        assert_eq!(super::quote_literal("pretty's"), "'pretty''s'");
+        assert_eq!(super::quote_literal(r"C:\pretty"), r"E'C:\\pretty'");
+    }
+
+    #[test]
+    fn discover() {
+        let dir = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/versioned"
+        ));
+        let (control_file, scripts) =
+            ControlFile::discover(dir).expect("failed to discover control file");
+        assert_eq!(control_file.default_version, "1.2");
+        assert_eq!(
+            scripts,
+            vec![
+                VersionScript {
+                    path: dir.join("versioned--1.0--1.1.sql"),
+                    from_version: Some("1.0".to_string()),
+                    to_version: "1.1".to_string(),
+                },
+                VersionScript {
+                    path: dir.join("versioned--1.0.sql"),
+                    from_version: None,
+                    to_version: "1.0".to_string(),
+                },
+                VersionScript {
+                    path: dir.join("versioned--1.1--1.2.sql"),
+                    from_version: Some("1.1".to_string()),
+                    to_version: "1.2".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_without_control_file() {
+        let dir = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src"));
+        match ControlFile::discover(dir) {
+            Err(ControlFileError::Discovery { found, .. }) => assert!(found.is_empty()),
+            other => panic!("expected a Discovery error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_str_skips_comments() {
+        let control_file = ControlFile::from_str(
+            "# versioned extension\n\
+             comment = 'versioned'\n\
+             # default_version = '2.0'\n\
+             default_version = '1.0'\n\
+             \t# module_pathname = '$libdir/old'\n\
+             module_pathname = '$libdir/versioned'\n\
+             relocatable = false\n\
+             superuser = false\n",
+        )
+        .expect("failed to parse control file");
+        assert_eq!(control_file.default_version, "1.0");
+        assert_eq!(control_file.module_pathname, "$libdir/versioned");
+    }
+
+    #[test]
+    fn from_str_strips_inline_comments() {
+        let control_file = ControlFile::from_str(
+            "comment = 'issue #42, fixed' # quoted, so the first # isn't a comment\n\
+             default_version = 1.0 # initial\n\
+             module_pathname = '$libdir/versioned'# no space\n\
+             relocatable = false  # not relocatable\n\
+             superuser = true # needs = superuser\n",
+        )
+        .expect("failed to parse control file");
+        assert_eq!(control_file.comment, "issue #42, fixed");
+        assert_eq!(control_file.default_version, "1.0");
+        assert_eq!(control_file.module_pathname, "$libdir/versioned");
+        assert!(!control_file.relocatable);
+        assert!(control_file.superuser);
+    }
+
+    /// A small xorshift generator, so the inputs of [`from_str_never_panics()`] are the same on
+    /// every run
+    struct Xorshift(u64);
+
+    impl Xorshift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    const VALID: &str = "comment = 'valid'\n\
+                         default_version = '1.0'\n\
+                         module_pathname = '$libdir/valid'\n\
+                         relocatable = false\n\
+                         superuser = true\n";
+
+    #[test]
+    fn from_str_never_panics() {
+        // pieces of `.control` syntax, so that most inputs are close to, or are, valid settings
+        const FRAGMENTS: &[&str] = &[
+            "comment",
+            "default_version",
+            "module_pathname",
+            "relocatable",
+            "superuser",
+            "schema",
+            "true",
+            "false",
+            "=",
+            "'",
+            "#",
+            " ",
+            "\t",
+            "\n",
+            "\r\n",
+            "\0",
+            "é",
+            "€",
+            "🦀",
+            "\u{feff}",
+        ];
+        let mut rng = Xorshift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..10_000 {
+            let mut input = String::new();
+            for _ in 0..rng.below(64) {
+                if rng.below(4) == 0 {
+                    input.push(char::from(rng.below(128) as u8));
+                } else {
+                    input.push_str(FRAGMENTS[rng.below(FRAGMENTS.len())]);
+                }
+            }
+            let _ = ControlFile::from_str(&input);
+
+            // settings still parse with arbitrary lines around them
+            let parsed = ControlFile::from_str(&format!("{}\n{}", input.replace('=', ""), VALID))
+                .unwrap_or_else(|error| panic!("{:?} failed to parse: {}", input, error));
+            assert_eq!(parsed.comment, "valid");
+            assert!(parsed.superuser);
+
+            let bytes = (0..rng.below(64))
+                .map(|_| rng.next() as u8)
+                .collect::<Vec<_>>();
+            let _ = ControlFile::try_from(bytes.as_slice());
+        }
+    }
+
+    #[test]
+    fn from_str_commented_out_field_is_missing() {
+        match ControlFile::from_str(
+            "comment = 'versioned'\n\
+             # default_version = '2.0'\n\
+             module_pathname = '$libdir/versioned'\n\
+             relocatable = false\n\
+             superuser = false\n",
+        ) {
+            Err(ControlFileError::MissingField {
+                field: "default_version",
+                ..
+            }) => (),
+            other => panic!("expected a MissingField error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_str_missing_fields() {
+        const FIELDS: &[(&str, &str)] = &[
+            ("comment", "'versioned'"),
+            ("default_version", "'1.0'"),
+            ("module_pathname", "'$libdir/versioned'"),
+            ("relocatable", "false"),
+            ("superuser", "false"),
+        ];
+
+        for (missing, _) in FIELDS {
+            let input = FIELDS
+                .iter()
+                .filter(|(field, _)| field != missing)
+                .map(|(field, value)| format!("{} = {}\n", field, value))
+                .collect::<String>();
+
+            let error = ControlFile::from_str(&input).expect_err("parsed without a field");
+            assert_eq!(error.field(), Some(*missing));
+            assert_eq!(
+                error,
+                ControlFileError::MissingField {
+                    field: *missing,
+                    context: SpanTrace::capture(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn errors_of_different_kinds_differ() {
+        let missing = ControlFile::from_str("").expect_err("parsed an empty control file");
+        let io = ControlFile::try_from(Path::new("/this/path/does/not/exist.control"))
+            .expect_err("read a nonexistent control file");
+        assert_eq!(io.field(), None);
+        assert_ne!(missing, io);
+        assert_eq!(
+            io,
+            ControlFile::try_from(Path::new("/this/path/does/not/exist.control"))
+                .expect_err("read a nonexistent control file")
+        );
+    }
+
+    #[test]
+    fn changes_from_relocatable() {
+        let old = ControlFile::from_str(
+            "comment = 'versioned'\n\
+             default_version = '1.0'\n\
+             module_pathname = '$libdir/versioned'\n\
+             relocatable = false\n\
+             superuser = false\n",
+        )
+        .expect("failed to parse control file");
+        let new = ControlFile {
+            relocatable: true,
+            ..old.clone()
+        };
+
+        let changes = new.changes_from(&old);
+        assert_eq!(
+            changes,
+            vec![ControlFieldChange::Relocatable {
+                old: false,
+                new: true
+            }]
+        );
+        assert_eq!(changes[0].field(), "relocatable");
+        assert!(changes[0].breaks_upgrades());
+        assert_eq!(
+            changes[0].to_string(),
+            "`relocatable` changed from `false` to `true`"
+        );
+        assert!(old.changes_from(&old).is_empty());
+    }
+}
@@ -27,6 +27,51 @@ impl ExtensionSqlEntity {
             .iter()
             .find(|created| created.has_sql_declared_entity(identifier))
     }
+
+    /// Whether the SQL has a `CREATE [OR REPLACE] FUNCTION` for a function named `name`, of any
+    /// schema and language, such as one in PL/pgSQL
+    ///
+    /// This is only a lightweight scan of the SQL, which doesn't skip comments or strings.
+    pub fn creates_function(&self, name: &str) -> bool {
+        created_function_names(self.sql)
+            .iter()
+            .any(|created| created.eq_ignore_ascii_case(name))
+    }
+}
+
+/// The names of the functions and procedures of each `CREATE [OR REPLACE] FUNCTION` (or
+/// `PROCEDURE`) in `sql`, without their schema or quotes
+fn created_function_names(sql: &str) -> Vec<String> {
+    let words = sql
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut names = Vec::new();
+    for (idx, word) in words.iter().enumerate() {
+        if !word.eq_ignore_ascii_case("CREATE") {
+            continue;
+        }
+        let mut rest = words[idx + 1..].iter();
+        let mut kind = rest.next();
+        if matches!(kind, Some(or) if or.eq_ignore_ascii_case("OR")) {
+            match rest.next() {
+                Some(replace) if replace.eq_ignore_ascii_case("REPLACE") => kind = rest.next(),
+                _ => continue,
+            }
+        }
+        match (kind, rest.next()) {
+            (Some(kind), Some(name))
+                if kind.eq_ignore_ascii_case("FUNCTION")
+                    || kind.eq_ignore_ascii_case("PROCEDURE") =>
+            {
+                let name = name.rsplit('.').next().unwrap_or(name);
+                names.push(name.trim_matches('"').to_string());
+            }
+            _ => (),
+        }
+    }
+    names
 }
 
 impl Into<SqlGraphEntity> for ExtensionSqlEntity {
@@ -53,8 +98,16 @@ impl SqlGraphIdentifier for ExtensionSqlEntity {
 }
 
 impl ToSql for ExtensionSqlEntity {
-    #[tracing::instrument(level = "debug", skip(self, _context), fields(identifier = self.full_path))]
-    fn to_sql(&self, _context: &super::PgxSql) -> eyre::Result<String> {
+    #[tracing::instrument(level = "debug", skip(self, context), fields(identifier = self.full_path))]
+    fn to_sql(&self, context: &super::PgxSql) -> eyre::Result<String> {
+        // only the block's own `#[pg_schema]` module decides its schema, not whatever it's
+        // connected to in the graph through `bootstrap` or `requires`
+        let schema = context
+            .schemas
+            .keys()
+            .find(|schema| schema.module_path == self.module_path)
+            .map(|schema| schema.name)
+            .unwrap_or("@extschema@");
         let sql = format!(
             "\n\
                 -- {file}:{line}\n\
@@ -98,7 +151,7 @@ impl ToSql for ExtensionSqlEntity {
                 "".to_string()
             },
             finalize = if self.finalize { "-- finalize\n" } else { "" },
-            sql = self.sql,
+            sql = self.sql.replace("{schema}", schema),
         );
         tracing::debug!(%sql);
         Ok(sql)
@@ -136,7 +189,7 @@ impl Display for SqlDeclaredEntity {
                 f.write_str(&(String::from("Enum(") + &data.name + ")"))
             }
             SqlDeclaredEntity::Function(data) => {
-                f.write_str(&(String::from("Function ") + &data.name + ")"))
+                f.write_str(&(String::from("Function(") + &data.name + ")"))
             }
         }
     }
@@ -189,6 +242,15 @@ impl SqlDeclaredEntity {
         }
     }
 
+    /// The Rust path this entity was declared with, such as `my_ext::submod::Cust`
+    pub fn name(&self) -> &str {
+        match self {
+            SqlDeclaredEntity::Type(data) => &data.name,
+            SqlDeclaredEntity::Enum(data) => &data.name,
+            SqlDeclaredEntity::Function(data) => &data.name,
+        }
+    }
+
     pub fn has_sql_declared_entity(&self, identifier: &SqlDeclared) -> bool {
         match (&identifier, &self) {
             (SqlDeclared::Type(identifier_name), &SqlDeclaredEntity::Type(data))
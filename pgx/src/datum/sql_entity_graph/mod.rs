@@ -2,14 +2,15 @@ mod pgx_sql;
 pub use pgx_sql::PgxSql;
 
 mod control_file;
-pub use control_file::ControlFile;
+pub use control_file::{ControlFieldChange, ControlFile, ControlFileError, VersionScript};
 
 mod schema;
 pub use schema::SchemaEntity;
 
 mod pg_extern;
 pub use pg_extern::{
-    PgExternArgumentEntity, PgExternEntity, PgExternReturnEntity, PgOperatorEntity,
+    FunctionSignature, PgExternArgumentEntity, PgExternEntity, PgExternReturnEntity,
+    PgOperatorEntity,
 };
 
 mod extension_sql;
@@ -10,6 +10,7 @@ pub struct PgExternArgumentEntity {
     pub module_path: String,
     pub is_optional: bool,
     pub is_variadic: bool,
+    pub is_inout: bool,
     pub default: Option<&'static str>,
 }
 
@@ -1,17 +1,18 @@
 mod argument;
 mod operator;
 mod returning;
+mod signature;
 
 use eyre::eyre as eyre_err;
 
 pub use argument::PgExternArgumentEntity;
 pub use operator::PgOperatorEntity;
 pub use returning::PgExternReturnEntity;
+pub use signature::FunctionSignature;
 
 use pgx_utils::ExternArgs;
 
 use super::{SqlGraphEntity, SqlGraphIdentifier, ToSql};
-use pgx_utils::sql_entity_graph::SqlDeclared;
 use std::cmp::Ordering;
 
 /// The output of a [`Schema`](crate::datum::sql_entity_graph::Schema) from `quote::ToTokens::to_tokens`.
@@ -48,6 +49,57 @@ impl PartialOrd for PgExternEntity {
 
 impl crate::PostgresType for PgExternEntity {}
 
+impl PgExternEntity {
+    /// Whether this is a `#[pg_proc]` procedure, rather than a function
+    pub fn is_procedure(&self) -> bool {
+        self.extern_attrs.contains(&ExternArgs::Procedure)
+    }
+
+    /// The SQL type of each argument, schema-qualified as it's declared in the `CREATE FUNCTION`
+    fn argument_sql_types(&self, context: &super::PgxSql) -> eyre::Result<Vec<String>> {
+        let self_index = context.externs[self];
+        self.fn_args
+            .iter()
+            .map(|arg| {
+                let graph_index = context
+                    .graph
+                    .neighbors_undirected(self_index)
+                    .find(|neighbor| match &context.graph[*neighbor] {
+                        SqlGraphEntity::Type(ty) => ty.id_matches(&arg.ty_id),
+                        SqlGraphEntity::Enum(en) => en.id_matches(&arg.ty_id),
+                        SqlGraphEntity::BuiltinType(defined) => defined == &arg.full_path,
+                        _ => false,
+                    })
+                    .ok_or_else(|| eyre_err!("Could not find arg type in graph. Got: {:?}", arg))?;
+                // First try to match on [`TypeId`] since it's most reliable.
+                let sql_type = context
+                    .rust_to_sql(arg.ty_id, arg.ty_source, arg.full_path)
+                    .ok_or_else(|| {
+                        eyre_err!(
+                            "Failed to map argument `{}` type `{}` to SQL type while building function `{}`.",
+                            arg.pattern,
+                            arg.full_path,
+                            self.name
+                        )
+                    })?;
+                Ok(context.schema_prefix_for(&graph_index) + &sql_type)
+            })
+            .collect()
+    }
+
+    /// The schema, name, and argument types the function is created with
+    pub fn signature(&self, context: &super::PgxSql) -> eyre::Result<FunctionSignature> {
+        Ok(FunctionSignature {
+            schema: self
+                .schema
+                .map(String::from)
+                .or_else(|| context.schema_alias_of(&context.externs[self])),
+            name: self.name.to_string(),
+            arguments: self.argument_sql_types(context)?,
+        })
+    }
+}
+
 impl Into<SqlGraphEntity> for PgExternEntity {
     fn into(self) -> SqlGraphEntity {
         SqlGraphEntity::Function(self)
@@ -79,9 +131,27 @@ impl ToSql for PgExternEntity {
     )]
     fn to_sql(&self, context: &super::PgxSql) -> eyre::Result<String> {
         let self_index = context.externs[self];
-        let mut extern_attrs = self.extern_attrs.clone();
+        let is_procedure = self.is_procedure();
+        // a procedure has none of the attributes of a function, which `#[pg_proc]` rejects, and
+        // the rest have no SQL of their own
+        let mut extern_attrs = if is_procedure {
+            let unsupported = self.extern_attrs.iter().find(|attr| {
+                matches!(attr, ExternArgs::Support(_)) || !attr.to_string().is_empty()
+            });
+            if let Some(unsupported) = unsupported {
+                return Err(eyre_err!(
+                    "Procedure `{}` can't be created with `{:?}`",
+                    self.full_path,
+                    unsupported
+                ));
+            }
+            Vec::new()
+        } else {
+            self.extern_attrs.clone()
+        };
         // if we already have a STRICT marker we do not need to add it
-        let mut strict_upgrade = !extern_attrs.iter().any(|i| i == &ExternArgs::Strict);
+        let mut strict_upgrade =
+            !is_procedure && !extern_attrs.iter().any(|i| i == &ExternArgs::Strict);
         if strict_upgrade {
             for arg in &self.fn_args {
                 if arg.is_optional {
@@ -94,52 +164,67 @@ impl ToSql for PgExternEntity {
             extern_attrs.push(ExternArgs::Strict);
         }
 
+        let support = self
+            .extern_attrs
+            .iter()
+            .find_map(|x| match x {
+                ExternArgs::Support(support) => Some(support),
+                _ => None,
+            })
+            .map(|support| {
+                let support_index = super::pgx_sql::find_positioning_ref_target(
+                    support,
+                    &context.types,
+                    &context.enums,
+                    &context.externs,
+                    &context.schemas,
+                    &context.extension_sqls,
+                )
+                .ok_or_else(|| eyre_err!("Could not find `support` target: {:?}", support))?;
+                match &context.graph[*support_index] {
+                    SqlGraphEntity::Function(support_fn) => Ok(format!(
+                        "SUPPORT {schema}\"{name}\"\n",
+                        schema = support_fn
+                            .schema
+                            .map(|schema| format!("{}.", schema))
+                            .unwrap_or_else(|| context.schema_prefix_for(support_index)),
+                        name = support_fn.name,
+                    )),
+                    _ => Err(eyre_err!(
+                        "`support` target `{}` of `{}` is not a function",
+                        support,
+                        self.name
+                    )),
+                }
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let argument_sql_types = self.argument_sql_types(context)?;
         let fn_sql = format!("\
-                                CREATE OR REPLACE FUNCTION {schema}\"{name}\"({arguments}) {returns}\n\
+                                CREATE OR REPLACE {function_or_procedure} {schema}\"{name}\"({arguments}){returns}\n\
                                 {extern_attrs}\
+                                {support}\
                                 {search_path}\
                                 LANGUAGE c /* Rust */\n\
                                 AS 'MODULE_PATHNAME', '{unaliased_name}_wrapper';\
                             ",
+                             function_or_procedure = if is_procedure { "PROCEDURE" } else { "FUNCTION" },
                              schema = self.schema.map(|schema| format!("{}.", schema)).unwrap_or_else(|| context.schema_prefix_for(&self_index)),
                              name = self.name,
                              unaliased_name = self.unaliased_name,
                              arguments = if !self.fn_args.is_empty() {
                                  let mut args = Vec::new();
                                  for (idx, arg) in self.fn_args.iter().enumerate() {
-                                     let graph_index = context.graph.neighbors_undirected(self_index).find(|neighbor| match &context.graph[*neighbor] {
-                                         SqlGraphEntity::Type(ty) => ty.id_matches(&arg.ty_id),
-                                         SqlGraphEntity::Enum(en) => en.id_matches(&arg.ty_id),
-                                         SqlGraphEntity::BuiltinType(defined) => defined == &arg.full_path,
-                                         _ => false,
-                                     }).ok_or_else(|| eyre_err!("Could not find arg type in graph. Got: {:?}", arg))?;
                                      let needs_comma = idx < (self.fn_args.len() - 1);
                                      let buf = format!("\
-                                            \t\"{pattern}\" {variadic}{schema_prefix}{sql_type}{default}{maybe_comma}/* {full_path} */\
+                                            \t\"{pattern}\" {variadic}{inout}{sql_type}{default}{maybe_comma}/* {full_path} */\
                                         ",
                                             pattern = arg.pattern,
-                                            schema_prefix = context.schema_prefix_for(&graph_index),
-                                            // First try to match on [`TypeId`] since it's most reliable.
-                                            sql_type = context.source_only_to_sql_type(arg.ty_source).or_else(|| {
-                                                context.type_id_to_sql_type(arg.ty_id)
-                                            }).or_else(|| {
-                                                // Fall back to fuzzy matching.
-                                                let path = arg.full_path.to_string();
-                                                if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Type(path.clone())) {
-                                                    Some(found.sql())
-                                                }  else if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Enum(path.clone())) {
-                                                    Some(found.sql())
-                                                } else {
-                                                    None
-                                                }
-                                            }).ok_or_else(|| eyre_err!(
-                                                "Failed to map argument `{}` type `{}` to SQL type while building function `{}`.",
-                                                arg.pattern,
-                                                arg.full_path,
-                                                self.name
-                                            ))?,
+                                            sql_type = argument_sql_types[idx],
                                             default = if let Some(def) = arg.default { format!(" DEFAULT {}", def) } else { String::from("") },
                                             variadic = if arg.is_variadic { "VARIADIC " } else { "" },
+                                            inout = if arg.is_inout { "INOUT " } else { "" },
                                             maybe_comma = if needs_comma { ", " } else { " " },
                                             full_path = arg.full_path,
                                      );
@@ -148,7 +233,8 @@ impl ToSql for PgExternEntity {
                                  String::from("\n") + &args.join("\n") + "\n"
                              } else { Default::default() },
                              returns = match &self.fn_return {
-                                 PgExternReturnEntity::None => String::from("RETURNS void"),
+                                 _ if is_procedure => String::new(),
+                                 PgExternReturnEntity::None => String::from(" RETURNS void"),
                                  PgExternReturnEntity::Type { id, source, full_path, .. } => {
                                      let graph_index = context.graph.neighbors_undirected(self_index).find(|neighbor| match &context.graph[*neighbor] {
                                          SqlGraphEntity::Type(ty) => ty.id_matches(&id),
@@ -156,19 +242,8 @@ impl ToSql for PgExternEntity {
                                          SqlGraphEntity::BuiltinType(defined) => &*defined == full_path,
                                          _ => false,
                                      }).ok_or_else(|| eyre_err!("Could not find return type in graph."))?;
-                                     format!("RETURNS {schema_prefix}{sql_type} /* {full_path} */",
-                                             sql_type = context.source_only_to_sql_type(source).or_else(|| {
-                                                 context.type_id_to_sql_type(*id)
-                                             }).or_else(|| {
-                                                    let pat = full_path.to_string();
-                                                    if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Type(pat.clone())) {
-                                                        Some(found.sql())
-                                                    }  else if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Enum(pat.clone())) {
-                                                        Some(found.sql())
-                                                    } else {
-                                                        None
-                                                    }
-                                                }).ok_or_else(|| eyre_err!("Failed to map return type `{}` to SQL type while building function `{}`.", full_path, self.full_path))?,
+                                     format!(" RETURNS {schema_prefix}{sql_type} /* {full_path} */",
+                                             sql_type = context.rust_to_sql(*id, source, full_path).ok_or_else(|| eyre_err!("Failed to map return type `{}` to SQL type while building function `{}`.", full_path, self.full_path))?,
                                              schema_prefix = context.schema_prefix_for(&graph_index),
                                              full_path = full_path
                                      )
@@ -180,23 +255,14 @@ impl ToSql for PgExternEntity {
                                          SqlGraphEntity::BuiltinType(defined) => defined == full_path,
                                          _ => false,
                                      }).ok_or_else(|| eyre_err!("Could not find return type in graph."))?;
-                                     format!("RETURNS SETOF {schema_prefix}{sql_type} /* {full_path} */",
-                                             sql_type = context.source_only_to_sql_type(source).or_else(|| {
-                                                 context.type_id_to_sql_type(*id)
-                                             }).or_else(|| {
-                                                    let pat = full_path.to_string();
-                                                    if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Type(pat.clone())) {
-                                                        Some(found.sql())
-                                                    }  else if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Enum(pat.clone())) {
-                                                        Some(found.sql())
-                                                    } else {
-                                                        None
-                                                    }
-                                                }).ok_or_else(|| eyre_err!("Failed to map return type `{}` to SQL type while building function `{}`.", full_path, self.full_path))?,
+                                     format!(" RETURNS SETOF {schema_prefix}{sql_type} /* {full_path} */",
+                                             sql_type = context.rust_to_sql(*id, source, full_path).ok_or_else(|| eyre_err!("Failed to map return type `{}` to SQL type while building function `{}`.", full_path, self.full_path))?,
                                              schema_prefix = context.schema_prefix_for(&graph_index),
                                              full_path = full_path
                                      )
                                  },
+                                 // the tuple of new values of `INOUT` arguments is a single record
+                                 PgExternReturnEntity::Iterated(_) if self.fn_args.iter().any(|arg| arg.is_inout) => String::from(" RETURNS record"),
                                  PgExternReturnEntity::Iterated(table_items) => {
                                      let mut items = String::new();
                                      for (idx, (id, source, ty_name, _module_path, col_name)) in table_items.iter().enumerate() {
@@ -212,27 +278,17 @@ impl ToSql for PgExternEntity {
                                                             schema_prefix = if let Some(graph_index) = graph_index {
                                                                 context.schema_prefix_for(&graph_index)
                                                             } else { "".into() },
-                                                            ty_resolved = context.source_only_to_sql_type(source).or_else(|| {
-                                                                context.type_id_to_sql_type(*id)
-                                                            }).or_else(|| {
-                                                                let pat = ty_name.to_string();
-                                                                if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Type(pat.clone())) {
-                                                                    Some(found.sql())
-                                                                }  else if let Some(found) = context.has_sql_declared_entity(&SqlDeclared::Enum(pat.clone())) {
-                                                                    Some(found.sql())
-                                                                } else {
-                                                                    None
-                                                                }
-                                                            }).ok_or_else(|| eyre_err!("Failed to map return type `{}` to SQL type while building function `{}`.", ty_name, self.name))?,
+                                                            ty_resolved = context.rust_to_sql(*id, source, ty_name).ok_or_else(|| eyre_err!("Failed to map return type `{}` to SQL type while building function `{}`.", ty_name, self.name))?,
                                                             needs_comma = if needs_comma { ", " } else { " " },
                                                             ty_name = ty_name
                                          );
                                          items.push_str(&item);
                                      }
-                                     format!("RETURNS TABLE ({}\n)", items)
+                                     format!(" RETURNS TABLE ({}\n)", items)
                                  },
-                                 PgExternReturnEntity::Trigger => String::from("RETURNS trigger"),
+                                 PgExternReturnEntity::Trigger => String::from(" RETURNS trigger"),
                              },
+                             support = support,
                              search_path = if let Some(search_path) = &self.search_path {
                                  let retval = format!("SET search_path TO {}", search_path.join(", "));
                                  retval + "\n"
@@ -251,6 +307,7 @@ impl ToSql for PgExternEntity {
                                 -- {file}:{line}\n\
                                 -- {module_path}::{name}\n\
                                 {requires}\
+                                {drop_stale}\
                                 {fn_sql}\
                                 {overridden}\
                             ",
@@ -258,6 +315,11 @@ impl ToSql for PgExternEntity {
             module_path = self.module_path,
             file = self.file,
             line = self.line,
+            drop_stale = context
+                .stale_functions_of(self)?
+                .iter()
+                .map(|stale| format!("DROP FUNCTION IF EXISTS {};\n", stale))
+                .collect::<String>(),
             fn_sql = if self.overridden.is_some() {
                 let mut inner = fn_sql
                     .lines()
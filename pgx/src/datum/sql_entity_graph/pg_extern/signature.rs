@@ -0,0 +1,169 @@
+use eyre::eyre as eyre_err;
+use std::{fmt, str::FromStr};
+
+/// The identity of a SQL function: its schema, its name, and the SQL types of its arguments, as
+/// `DROP FUNCTION` needs them.
+///
+/// Written out as `schema."name"(integer, text)`, one per line, these make up the manifest of
+/// [`PgxSql::function_manifest()`](crate::datum::sql_entity_graph::PgxSql::function_manifest),
+/// which a later version can compare its own functions against through
+/// [`PgxSql::previous_functions`](crate::datum::sql_entity_graph::PgxSql::previous_functions).
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FunctionSignature {
+    /// The schema the function is created in, or `None` if it's created without one.
+    pub schema: Option<String>,
+    pub name: String,
+    pub arguments: Vec<String>,
+}
+
+impl FunctionSignature {
+    /// Parse a manifest of one signature per line, skipping blank lines and `--` comments.
+    pub fn parse_manifest(manifest: &str) -> eyre::Result<Vec<Self>> {
+        manifest
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("--"))
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl fmt::Display for FunctionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(schema) = &self.schema {
+            write!(f, "{}.", schema)?;
+        }
+        write!(f, "\"{}\"({})", self.name, self.arguments.join(", "))
+    }
+}
+
+impl FromStr for FunctionSignature {
+    type Err = eyre::Report;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || eyre_err!("Invalid function signature `{}`", input);
+        let open = input.find('(').ok_or_else(invalid)?;
+        let arguments = input[open + 1..]
+            .strip_suffix(')')
+            .ok_or_else(invalid)?
+            .trim();
+        let head = input[..open].trim();
+        let (schema, name) = match head.strip_suffix('"') {
+            // a quoted name may itself contain a `.`
+            Some(quoted) => {
+                let start = quoted.rfind('"').ok_or_else(invalid)?;
+                let schema = match &quoted[..start] {
+                    "" => None,
+                    schema => Some(schema.strip_suffix('.').ok_or_else(invalid)?),
+                };
+                (schema, &quoted[start + 1..])
+            }
+            None => match head.rsplit_once('.') {
+                Some((schema, name)) => (Some(schema), name),
+                None => (None, head),
+            },
+        };
+        if schema == Some("") || name.is_empty() {
+            return Err(invalid());
+        }
+
+        // split on the commas between arguments, but not those of a type modifier, like the
+        // `numeric(10, 2)` of an argument
+        let mut split = Vec::new();
+        let mut depth = 0;
+        let mut start = 0;
+        for (idx, c) in arguments.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    split.push(arguments[start..idx].trim().to_string());
+                    start = idx + 1;
+                }
+                _ => (),
+            }
+        }
+        if !arguments.is_empty() {
+            split.push(arguments[start..].trim().to_string());
+        }
+        if split.iter().any(String::is_empty) {
+            return Err(invalid());
+        }
+
+        Ok(FunctionSignature {
+            schema: schema.map(str::to_string),
+            name: name.to_string(),
+            arguments: split,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FunctionSignature;
+
+    #[test]
+    fn from_str_round_trips() {
+        for (input, schema, name, arguments) in [
+            (
+                "\"add\"(integer, integer)",
+                None,
+                "add",
+                vec!["integer", "integer"],
+            ),
+            ("\"now\"()", None, "now", vec![]),
+            (
+                "util.\"add\"(util.Color)",
+                Some("util"),
+                "add",
+                vec!["util.Color"],
+            ),
+            (
+                "\"a.b\"(numeric(10, 2))",
+                None,
+                "a.b",
+                vec!["numeric(10, 2)"],
+            ),
+        ] {
+            let signature = input.parse::<FunctionSignature>().expect(input);
+            assert_eq!(signature.schema.as_deref(), schema, "{}", input);
+            assert_eq!(signature.name, name, "{}", input);
+            assert_eq!(signature.arguments, arguments, "{}", input);
+            assert_eq!(signature.to_string(), input);
+        }
+
+        let signature = "util.add( integer ,text )".parse::<FunctionSignature>();
+        assert_eq!(
+            signature.expect("unquoted").to_string(),
+            "util.\"add\"(integer, text)"
+        );
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        for input in [
+            "add",
+            "\"add\"(integer",
+            "\"add\"(integer,)",
+            "(integer)",
+            ".\"add\"()",
+        ] {
+            assert!(input.parse::<FunctionSignature>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_manifest_skips_comments() {
+        let signatures = FunctionSignature::parse_manifest(
+            "-- my_extension 1.0\n\n\"add\"(integer, integer)\n  \"now\"()  \n",
+        )
+        .expect("failed to parse manifest");
+        assert_eq!(
+            signatures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["\"add\"(integer, integer)", "\"now\"()"]
+        );
+    }
+}
@@ -1,12 +1,23 @@
 use eyre::eyre as eyre_err;
-use std::{any::TypeId, collections::HashMap, fmt::Debug};
+use std::{
+    any::TypeId,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Debug,
+};
 
-use petgraph::{dot::Dot, graph::NodeIndex, stable_graph::StableGraph};
+use petgraph::{
+    dot::Dot,
+    graph::NodeIndex,
+    stable_graph::StableGraph,
+    visit::EdgeRef,
+    Direction::{Incoming, Outgoing},
+};
 use tracing::instrument;
 
 use super::{
-    ControlFile, ExtensionSqlEntity, PgExternEntity, PgExternReturnEntity, PositioningRef,
-    PostgresEnumEntity, PostgresHashEntity, PostgresOrdEntity, PostgresTypeEntity,
+    ControlFile, ExtensionSqlEntity, FunctionSignature, PgExternEntity, PgExternReturnEntity,
+    PositioningRef, PostgresEnumEntity, PostgresHashEntity, PostgresOrdEntity, PostgresTypeEntity,
     RustSourceOnlySqlMapping, RustSqlMapping, SchemaEntity, SqlDeclaredEntity, SqlGraphEntity,
     SqlGraphIdentifier, ToSql,
 };
@@ -43,6 +54,19 @@ pub struct PgxSql {
     pub enums: HashMap<PostgresEnumEntity, NodeIndex>,
     pub ords: HashMap<PostgresOrdEntity, NodeIndex>,
     pub hashes: HashMap<PostgresHashEntity, NodeIndex>,
+    /// Guard each `CREATE TYPE` so that it's skipped if the type already exists, which lets the
+    /// generated SQL be re-applied to a database it was already applied to.  Postgres has no
+    /// `CREATE TYPE IF NOT EXISTS`, so this wraps them in a `DO` block instead.  Schemas are
+    /// always created with `IF NOT EXISTS`.
+    pub if_not_exists: bool,
+    /// The functions of the previous version of the extension, usually parsed from its
+    /// [`PgxSql::function_manifest()`] with [`FunctionSignature::parse_manifest()`].
+    ///
+    /// A `CREATE OR REPLACE FUNCTION` whose argument types changed creates a new overload rather
+    /// than replacing the old one, so each of these that no function of this version has the
+    /// schema, name, and argument types of, but some function has the schema and name of, is
+    /// dropped with a `DROP FUNCTION IF EXISTS` before that function is created.
+    pub previous_functions: Vec<FunctionSignature>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
@@ -182,28 +206,23 @@ impl PgxSql {
             graph_root: root,
             graph_bootstrap: bootstrap,
             graph_finalize: finalize,
+            if_not_exists: false,
+            previous_functions: Vec::default(),
         };
         this.register_types();
+        this.validate_type_mappings()?;
         Ok(this)
     }
 
     #[instrument(level = "error", skip(self))]
     pub fn to_file(&self, file: impl AsRef<str> + Debug) -> eyre::Result<()> {
-        use std::{
-            fs::{create_dir_all, File},
-            io::Write,
-            path::Path,
-        };
-        let generated = self.to_sql()?;
-        let path = Path::new(file.as_ref());
+        write_file(file, &self.to_sql()?)
+    }
 
-        let parent = path.parent();
-        if let Some(parent) = parent {
-            create_dir_all(parent)?;
-        }
-        let mut out = File::create(path)?;
-        write!(out, "{}", generated)?;
-        Ok(())
+    /// Like [`PgxSql::to_file`], but writes [`PgxSql::to_sql_pretty`]
+    #[instrument(level = "error", skip(self))]
+    pub fn to_file_pretty(&self, file: impl AsRef<str> + Debug) -> eyre::Result<()> {
+        write_file(file, &self.to_sql_pretty()?)
     }
 
     #[instrument(level = "error", err, skip(self))]
@@ -302,6 +321,110 @@ impl PgxSql {
             .unwrap_or_else(|| "".to_string())
     }
 
+    /// The `CREATE TYPE` statement `create_type`, guarded if [`PgxSql::if_not_exists`] is set
+    pub fn create_type_sql(&self, create_type: &str) -> String {
+        if self.if_not_exists {
+            format!(
+                "DO $$ BEGIN\n\
+                    {create_type}\n\
+                 EXCEPTION WHEN duplicate_object THEN NULL;\n\
+                 END $$;",
+                create_type = create_type
+                    .lines()
+                    .map(|line| format!("\t{}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        } else {
+            create_type.to_string()
+        }
+    }
+
+    /// The signature of every function, one per line, to be the [`PgxSql::previous_functions`]
+    /// of the next version
+    ///
+    /// Procedures aren't included.
+    pub fn function_manifest(&self) -> eyre::Result<String> {
+        let mut signatures = self
+            .externs
+            .keys()
+            .filter(|function| !function.is_procedure())
+            .map(|function| function.signature(self))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        signatures.sort();
+        Ok(signatures
+            .iter()
+            .map(|signature| format!("{}\n", signature))
+            .collect())
+    }
+
+    /// Write [`PgxSql::function_manifest`] to `file`
+    #[instrument(level = "error", skip(self))]
+    pub fn function_manifest_to_file(&self, file: impl AsRef<str> + Debug) -> eyre::Result<()> {
+        write_file(file, &self.function_manifest()?)
+    }
+
+    /// Set [`PgxSql::previous_functions`] to those of the manifest in `file`, as written by
+    /// [`PgxSql::function_manifest_to_file`]
+    #[instrument(level = "error", skip(self))]
+    pub fn previous_functions_from_file(
+        &mut self,
+        file: impl AsRef<str> + Debug,
+    ) -> eyre::Result<()> {
+        let manifest = std::fs::read_to_string(file.as_ref())?;
+        self.previous_functions = FunctionSignature::parse_manifest(&manifest)?;
+        Ok(())
+    }
+
+    /// The [`PgxSql::previous_functions`] to drop before `function` is created
+    ///
+    /// These are all dropped before the first function of their schema and name, by its position
+    /// in the source, so the others of that schema and name have none.
+    pub fn stale_functions_of(
+        &self,
+        function: &PgExternEntity,
+    ) -> eyre::Result<Vec<&FunctionSignature>> {
+        if self.previous_functions.is_empty()
+            || function.is_procedure()
+            || function.overridden.is_some()
+        {
+            return Ok(Vec::new());
+        }
+
+        let signature = function.signature(self)?;
+        let mut first = function;
+        let mut current_arguments = Vec::new();
+        for other in self.externs.keys() {
+            if other.name != function.name || other.is_procedure() {
+                continue;
+            }
+            let other_signature = other.signature(self)?;
+            if other_signature.schema != signature.schema {
+                continue;
+            }
+            if other.overridden.is_none()
+                && (other.file, other.line, other.full_path)
+                    < (first.file, first.line, first.full_path)
+            {
+                first = other;
+            }
+            current_arguments.push(other_signature.arguments);
+        }
+        if first != function {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .previous_functions
+            .iter()
+            .filter(|previous| {
+                previous.schema == signature.schema
+                    && previous.name == signature.name
+                    && !current_arguments.contains(&previous.arguments)
+            })
+            .collect())
+    }
+
     #[instrument(level = "error", skip(self))]
     pub fn to_sql(&self) -> eyre::Result<String> {
         let mut full_sql = String::new();
@@ -320,6 +443,88 @@ impl PgxSql {
         Ok(full_sql)
     }
 
+    /// Like [`PgxSql::to_sql`], but groups the statements by the kind of entity under header
+    /// comments, with a blank line between each statement.
+    ///
+    /// Statements are still emitted in dependency order, so a group is split in two if something
+    /// in it depends on an entity of a later group.
+    #[instrument(level = "error", skip(self))]
+    pub fn to_sql_pretty(&self) -> eyre::Result<String> {
+        let steps = petgraph::algo::toposort(&self.graph, None).map_err(|e| {
+            eyre_err!(
+                "Failed to toposort SQL entities, node with cycle: {:?}",
+                self.graph[e.node_id()]
+            )
+        })?;
+        // A topological sort that, of the entities whose dependencies have all been emitted, picks
+        // the one in the earliest section, and then the earliest in the source.  Unlike the
+        // order `to_sql()` uses, this is stable between runs.
+        let sort_key = |step_id: NodeIndex| {
+            let entity = &self.graph[step_id];
+            Reverse((
+                SqlSection::of(entity),
+                entity.file().unwrap_or_default(),
+                entity.line().unwrap_or_default(),
+                entity.rust_identifier(),
+                step_id,
+            ))
+        };
+        let mut remaining_dependencies = steps
+            .iter()
+            .map(|step_id| {
+                let count = self.graph.edges_directed(*step_id, Incoming).count();
+                (*step_id, count)
+            })
+            .collect::<HashMap<_, _>>();
+        let mut ready = steps
+            .iter()
+            .filter(|step_id| remaining_dependencies[step_id] == 0)
+            .map(|step_id| sort_key(*step_id))
+            .collect::<BinaryHeap<_>>();
+
+        let mut full_sql = String::new();
+        let mut current_section = None;
+        while let Some(Reverse((section, _, _, _, step_id))) = ready.pop() {
+            let sql = match &self.graph[step_id] {
+                // unlike that of `to_sql()`, this header says the ordering is stable
+                SqlGraphEntity::ExtensionRoot(control) => control.to_sql_pretty(),
+                entity => entity.to_sql(self)?,
+            };
+            let sql = sql.trim();
+
+            if !sql.is_empty() {
+                if current_section != Some(section) {
+                    if let Some(header) = section.header() {
+                        full_sql.push_str(&format!(
+                            "{rule}\n-- {header}\n{rule}\n\n",
+                            rule = "-- ".to_string() + &"-".repeat(60),
+                            header = header
+                        ));
+                    }
+                    current_section = Some(section);
+                }
+                full_sql.push_str(sql);
+                full_sql.push_str("\n\n");
+            }
+
+            for edge in self.graph.edges_directed(step_id, Outgoing) {
+                let dependent = edge.target();
+                let count = remaining_dependencies
+                    .get_mut(&dependent)
+                    .expect("dependent was not part of the toposort");
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(sort_key(dependent));
+                }
+            }
+        }
+
+        let trimmed_len = full_sql.trim_end().len();
+        full_sql.truncate(trimmed_len);
+        full_sql.push('\n');
+        Ok(full_sql)
+    }
+
     #[instrument(level = "error", skip(self))]
     pub fn register_types(&mut self) {
         for (item, _index) in self.enums.clone() {
@@ -367,6 +572,80 @@ impl PgxSql {
         self.source_mappings.get(ty_source).map(|f| f.sql.clone())
     }
 
+    /// The SQL type of a Rust type, looked up by its source, then its [`TypeId`], then by any
+    /// `extension_sql!()` which `creates` a type or enum of the same `full_path`
+    pub fn rust_to_sql(&self, id: TypeId, source: &str, full_path: &str) -> Option<String> {
+        self.source_only_to_sql_type(source)
+            .or_else(|| self.type_id_to_sql_type(id))
+            .or_else(|| {
+                let path = full_path.to_string();
+                if let Some(found) = self.has_sql_declared_entity(&SqlDeclared::Type(path.clone()))
+                {
+                    Some(found.sql())
+                } else if let Some(found) = self.has_sql_declared_entity(&SqlDeclared::Enum(path)) {
+                    Some(found.sql())
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Ensure every `#[pg_extern]` argument and return type can be mapped to SQL, reporting all
+    /// of the ones which can't at once
+    fn validate_type_mappings(&self) -> eyre::Result<()> {
+        let mut externs = self.externs.keys().collect::<Vec<_>>();
+        externs.sort_by_key(|item| (item.file, item.line, item.full_path));
+
+        let mut unmapped = Vec::new();
+        for item in externs {
+            for arg in &item.fn_args {
+                if self
+                    .rust_to_sql(arg.ty_id, arg.ty_source, arg.full_path)
+                    .is_none()
+                {
+                    unmapped.push(format!(
+                        "`{}` argument `{}`: `{}`",
+                        item.full_path, arg.pattern, arg.full_path
+                    ));
+                }
+            }
+            let returns = match &item.fn_return {
+                PgExternReturnEntity::None | PgExternReturnEntity::Trigger => vec![],
+                PgExternReturnEntity::Type {
+                    id,
+                    source,
+                    full_path,
+                    ..
+                }
+                | PgExternReturnEntity::SetOf {
+                    id,
+                    source,
+                    full_path,
+                    ..
+                } => vec![(*id, *source, *full_path)],
+                PgExternReturnEntity::Iterated(table_items) => table_items
+                    .iter()
+                    .map(|(id, source, full_path, _, _)| (*id, *source, *full_path))
+                    .collect(),
+            };
+            for (id, source, full_path) in returns {
+                if self.rust_to_sql(id, source, full_path).is_none() {
+                    unmapped.push(format!("`{}` returns: `{}`", item.full_path, full_path));
+                }
+            }
+        }
+
+        if unmapped.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre_err!(
+                "Failed to map {} type(s) to SQL:\n\t{}",
+                unmapped.len(),
+                unmapped.join("\n\t")
+            ))
+        }
+    }
+
     pub fn map_type_to_sql_type<T: 'static>(&mut self, sql: impl AsRef<str> + Debug) {
         let sql = sql.as_ref().to_string();
         self.type_mappings.insert(
@@ -410,6 +689,18 @@ fn initialize_extension_sqls<'a>(
     let mut finalize = None;
     let mut mapped_extension_sqls = HashMap::default();
     for item in extension_sqls {
+        for created in &item.creates {
+            if let SqlDeclaredEntity::Function(_) = created {
+                if !item.creates_function(&created.sql()) {
+                    return Err(eyre_err!(
+                        "`extension_sql!()` `{}` declares that it creates `{}`, but has no `CREATE FUNCTION {}`",
+                        item.name,
+                        created,
+                        created.sql(),
+                    ));
+                }
+            }
+        }
         let entity: SqlGraphEntity = item.clone().into();
         let index = graph.add_node(entity);
         mapped_extension_sqls.insert(item.clone(), index);
@@ -453,6 +744,64 @@ fn initialize_extension_sqls<'a>(
     Ok((mapped_extension_sqls, bootstrap, finalize))
 }
 
+/// Write `contents` to `file`, creating its parent directories if they don't exist
+fn write_file(file: impl AsRef<str>, contents: &str) -> eyre::Result<()> {
+    use std::{
+        fs::{create_dir_all, File},
+        io::Write,
+        path::Path,
+    };
+    let path = Path::new(file.as_ref());
+
+    let parent = path.parent();
+    if let Some(parent) = parent {
+        create_dir_all(parent)?;
+    }
+    let mut out = File::create(path)?;
+    write!(out, "{}", contents)?;
+    Ok(())
+}
+
+/// The groups [`PgxSql::to_sql_pretty`] arranges statements into, in the order they're emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SqlSection {
+    Root,
+    Schemas,
+    Types,
+    Functions,
+    Operators,
+    OperatorClasses,
+    CustomSql,
+}
+
+impl SqlSection {
+    fn of(entity: &SqlGraphEntity) -> Self {
+        match entity {
+            SqlGraphEntity::ExtensionRoot(_) => SqlSection::Root,
+            SqlGraphEntity::Schema(_) => SqlSection::Schemas,
+            SqlGraphEntity::Type(_) | SqlGraphEntity::Enum(_) | SqlGraphEntity::BuiltinType(_) => {
+                SqlSection::Types
+            }
+            SqlGraphEntity::Function(item) if item.operator.is_some() => SqlSection::Operators,
+            SqlGraphEntity::Function(_) => SqlSection::Functions,
+            SqlGraphEntity::Ord(_) | SqlGraphEntity::Hash(_) => SqlSection::OperatorClasses,
+            SqlGraphEntity::CustomSql(_) => SqlSection::CustomSql,
+        }
+    }
+
+    fn header(&self) -> Option<&'static str> {
+        match self {
+            SqlSection::Root => None,
+            SqlSection::Schemas => Some("Schemas"),
+            SqlSection::Types => Some("Types"),
+            SqlSection::Functions => Some("Functions"),
+            SqlSection::Operators => Some("Operators"),
+            SqlSection::OperatorClasses => Some("Operator classes"),
+            SqlSection::CustomSql => Some("Custom SQL"),
+        }
+    }
+}
+
 /// A best effort attempt to find the related [`NodeIndex`] for some [`PositioningRef`].
 pub fn find_positioning_ref_target<'a>(
     positioning_ref: &'a PositioningRef,
@@ -490,6 +839,19 @@ pub fn find_positioning_ref_target<'a>(
                     return Some(&other_index);
                 }
             }
+            // Entities an `extension_sql!()` says it `creates`, with their full Rust path.
+            for (other, other_index) in extension_sqls {
+                let created = other.creates.iter().any(|created| {
+                    let created_segments = created.name().split("::").collect::<Vec<_>>();
+                    let created_module_path =
+                        created_segments[..created_segments.len() - 1].join("::");
+                    created_segments.last() == Some(last_segment)
+                        && created_module_path.ends_with(&module_path)
+                });
+                if created {
+                    return Some(&other_index);
+                }
+            }
         }
         PositioningRef::Name(name) => {
             for (other, other_index) in extension_sqls {
@@ -556,9 +918,22 @@ fn initialize_schemas(
     schemas: Vec<SchemaEntity>,
 ) -> eyre::Result<HashMap<SchemaEntity, NodeIndex>> {
     let mut mapped_schemas = HashMap::default();
+    // Several modules may declare the same schema, which should only be created once, so they
+    // all share the node of the first.
+    let mut schemas_by_name: HashMap<&'static str, NodeIndex> = HashMap::default();
     for item in schemas {
+        if let Some(&index) = schemas_by_name.get(item.name) {
+            tracing::debug!(
+                schema = item.name,
+                module_path = item.module_path,
+                "Schema already declared, sharing its node."
+            );
+            mapped_schemas.insert(item, index);
+            continue;
+        }
         let entity = item.clone().into();
         let index = graph.add_node(entity);
+        schemas_by_name.insert(item.name, index);
         mapped_schemas.insert(item, index);
         if let Some(bootstrap) = bootstrap {
             graph.add_edge(bootstrap, index, SqlGraphRelationship::RequiredBy);
@@ -576,7 +951,7 @@ fn connect_schemas(
     root: NodeIndex,
 ) {
     for (_item, &index) in schemas {
-        graph.add_edge(root, index, SqlGraphRelationship::RequiredBy);
+        graph.update_edge(root, index, SqlGraphRelationship::RequiredBy);
     }
 }
 
@@ -785,6 +1160,21 @@ fn connect_externs(
                         }
                     }
                 }
+                pgx_utils::ExternArgs::Support(support) => {
+                    if let Some(target) = find_positioning_ref_target(
+                        support,
+                        types,
+                        enums,
+                        externs,
+                        schemas,
+                        extension_sqls,
+                    ) {
+                        tracing::debug!(from = %item.rust_identifier(), to = %graph[*target].rust_identifier(), "Adding Extern after support function edge");
+                        graph.add_edge(*target, index, SqlGraphRelationship::RequiredBy);
+                    } else {
+                        return Err(eyre_err!("Could not find `support` target: {:?}", support));
+                    }
+                }
                 _ => (),
             }
         }
@@ -1048,3 +1438,700 @@ fn connect_hashes(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PgxSql;
+    use crate::datum::sql_entity_graph::{
+        ControlFile, ExtensionSqlEntity, FunctionSignature, PgExternArgumentEntity, PgExternEntity,
+        PgExternReturnEntity, PositioningRef, PostgresEnumEntity, SchemaEntity, SqlDeclaredEntity,
+        SqlGraphEntity,
+    };
+    use crate::{DEFAULT_SOURCE_ONLY_SQL_MAPPING, DEFAULT_TYPEID_SQL_MAPPING};
+    use pgx_utils::ExternArgs;
+    use std::any::TypeId;
+
+    fn i32_arg(pattern: &'static str) -> PgExternArgumentEntity {
+        PgExternArgumentEntity {
+            pattern,
+            ty_source: "i32",
+            ty_id: TypeId::of::<i32>(),
+            full_path: "i32",
+            module_path: String::from(""),
+            is_optional: false,
+            is_variadic: false,
+            is_inout: false,
+            default: None,
+        }
+    }
+
+    fn i32_function(name: &'static str, full_path: &'static str, line: u32) -> SqlGraphEntity {
+        SqlGraphEntity::Function(PgExternEntity {
+            name,
+            unaliased_name: name,
+            schema: None,
+            file: "src/lib.rs",
+            line,
+            module_path: "pretty",
+            full_path,
+            extern_attrs: vec![ExternArgs::Immutable],
+            search_path: None,
+            fn_args: vec![i32_arg("a"), i32_arg("b")],
+            fn_return: PgExternReturnEntity::Type {
+                id: TypeId::of::<i32>(),
+                source: "i32",
+                full_path: "i32",
+                module_path: String::from(""),
+            },
+            operator: None,
+            overridden: None,
+        })
+    }
+
+    fn extension_root() -> SqlGraphEntity {
+        SqlGraphEntity::ExtensionRoot(ControlFile {
+            comment: String::from("pretty"),
+            default_version: String::from("1.0"),
+            module_pathname: String::from("$libdir/pretty"),
+            relocatable: false,
+            superuser: false,
+            schema: None,
+            name: None,
+        })
+    }
+
+    fn build(entities: Vec<SqlGraphEntity>) -> PgxSql {
+        PgxSql::build(
+            DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(),
+            DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(),
+            entities.into_iter(),
+        )
+        .expect("failed to build the SQL entity graph")
+    }
+
+    #[test]
+    fn extension_root_comment() {
+        let sql = build(vec![SqlGraphEntity::ExtensionRoot(ControlFile {
+            comment: String::from("pretty's types"),
+            default_version: String::from("1.0"),
+            module_pathname: String::from("$libdir/pretty"),
+            relocatable: false,
+            superuser: false,
+            schema: None,
+            name: Some(String::from("pretty")),
+        })])
+        .to_sql()
+        .expect("failed to generate SQL");
+        assert!(
+            sql.contains("COMMENT ON EXTENSION \"pretty\" IS 'pretty''s types';"),
+            "{}",
+            sql
+        );
+
+        let sql = build(vec![extension_root()])
+            .to_sql()
+            .expect("failed to generate SQL");
+        assert!(!sql.contains("COMMENT ON EXTENSION"), "{}", sql);
+    }
+
+    #[test]
+    fn extern_requires_name_and_path() {
+        let mut uses_table = i32_function("uses_table", "pretty::uses_table", 1);
+        if let SqlGraphEntity::Function(func) = &mut uses_table {
+            func.extern_attrs.push(ExternArgs::Requires(vec![
+                PositioningRef::Name(String::from("create_table")),
+                PositioningRef::FullPath(String::from("pretty::add")),
+            ]));
+        }
+        let entities = vec![
+            extension_root(),
+            uses_table,
+            i32_function("add", "pretty::add", 10),
+            SqlGraphEntity::CustomSql(ExtensionSqlEntity {
+                module_path: "pretty",
+                full_path: "src/lib.rs:30",
+                sql: "CREATE TABLE things (id integer);",
+                file: "src/lib.rs",
+                line: 30,
+                name: "create_table",
+                bootstrap: false,
+                finalize: false,
+                requires: vec![],
+                creates: vec![],
+            }),
+        ];
+
+        let sql = build(entities).to_sql().expect("failed to generate SQL");
+
+        let uses_table = sql
+            .find("FUNCTION \"uses_table\"")
+            .expect("no CREATE FUNCTION for uses_table");
+        let create_table = sql.find("CREATE TABLE things").expect("no CREATE TABLE");
+        let add = sql
+            .find("FUNCTION \"add\"")
+            .expect("no CREATE FUNCTION for add");
+        assert!(create_table < uses_table, "{}", sql);
+        assert!(add < uses_table, "{}", sql);
+    }
+
+    #[test]
+    fn extern_requires_created_type() {
+        let mut uses_complex = i32_function("uses_complex", "pretty::uses_complex", 1);
+        if let SqlGraphEntity::Function(func) = &mut uses_complex {
+            func.extern_attrs
+                .push(ExternArgs::Requires(vec![PositioningRef::FullPath(
+                    String::from("Complex"),
+                )]));
+        }
+        let entities = vec![
+            extension_root(),
+            uses_complex,
+            SqlGraphEntity::CustomSql(ExtensionSqlEntity {
+                module_path: "pretty",
+                full_path: "src/lib.rs:30",
+                sql: "CREATE TYPE complex;",
+                file: "src/lib.rs",
+                line: 30,
+                name: "create_complex_shell_type",
+                bootstrap: false,
+                finalize: false,
+                requires: vec![],
+                creates: vec![SqlDeclaredEntity::build("Type", "pretty::Complex").unwrap()],
+            }),
+        ];
+
+        let sql = build(entities).to_sql().expect("failed to generate SQL");
+
+        let uses_complex = sql
+            .find("FUNCTION \"uses_complex\"")
+            .expect("no CREATE FUNCTION for uses_complex");
+        let create_type = sql.find("CREATE TYPE complex;").expect("no CREATE TYPE");
+        assert!(create_type < uses_complex, "{}", sql);
+    }
+
+    fn plpgsql_helper(sql: &'static str) -> SqlGraphEntity {
+        SqlGraphEntity::CustomSql(ExtensionSqlEntity {
+            module_path: "pretty",
+            full_path: "src/lib.rs:30",
+            sql,
+            file: "src/lib.rs",
+            line: 30,
+            name: "create_answer",
+            bootstrap: false,
+            finalize: false,
+            requires: vec![],
+            creates: vec![SqlDeclaredEntity::build("Function", "pretty::answer").unwrap()],
+        })
+    }
+
+    #[test]
+    fn extern_requires_created_plpgsql_function() {
+        let mut uses_answer = i32_function("uses_answer", "pretty::uses_answer", 1);
+        if let SqlGraphEntity::Function(func) = &mut uses_answer {
+            func.fn_args[1].default = Some("answer()");
+            func.extern_attrs
+                .push(ExternArgs::Requires(vec![PositioningRef::FullPath(
+                    String::from("answer"),
+                )]));
+        }
+        let entities = vec![
+            extension_root(),
+            uses_answer,
+            plpgsql_helper(
+                "CREATE OR REPLACE FUNCTION \"answer\"() RETURNS integer LANGUAGE plpgsql AS $$\n\
+                 BEGIN RETURN 42; END;\n\
+                 $$;",
+            ),
+        ];
+
+        let sql = build(entities).to_sql().expect("failed to generate SQL");
+
+        let uses_answer = sql
+            .find("FUNCTION \"uses_answer\"")
+            .expect("no CREATE FUNCTION for uses_answer");
+        let answer = sql
+            .find("FUNCTION \"answer\"")
+            .expect("no CREATE FUNCTION for answer");
+        assert!(answer < uses_answer, "{}", sql);
+        assert!(
+            sql.contains("-- creates:\n--   Function(pretty::answer)\n"),
+            "{}",
+            sql
+        );
+        assert!(
+            sql.contains("\t\"b\" integer DEFAULT answer() /* i32 */"),
+            "{}",
+            sql
+        );
+    }
+
+    #[test]
+    fn extension_sql_creates_missing_function() {
+        for sql in [
+            "CREATE FUNCTION public.answer() RETURNS integer LANGUAGE plpgsql AS $$ BEGIN RETURN 42; END $$;",
+            "create function ANSWER() returns integer language sql as 'SELECT 42';",
+        ] {
+            build(vec![extension_root(), plpgsql_helper(sql)]);
+        }
+
+        let error = PgxSql::build(
+            DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(),
+            DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(),
+            vec![
+                extension_root(),
+                plpgsql_helper(
+                    "CREATE FUNCTION answer_v2() RETURNS integer AS 'SELECT 42' LANGUAGE sql;",
+                ),
+            ]
+            .into_iter(),
+        )
+        .expect_err("built a graph with an undefined function");
+        assert_eq!(
+            error.to_string(),
+            "`extension_sql!()` `create_answer` declares that it creates `Function(pretty::answer)`, but has no `CREATE FUNCTION answer`"
+        );
+    }
+
+    fn schema_placeholder_sql(module_path: &'static str, bootstrap: bool) -> SqlGraphEntity {
+        SqlGraphEntity::CustomSql(ExtensionSqlEntity {
+            module_path,
+            full_path: "src/lib.rs:40",
+            sql: "CREATE TABLE {schema}.settings (name text);",
+            file: "src/lib.rs",
+            line: 40,
+            name: "create_settings",
+            bootstrap,
+            finalize: false,
+            requires: vec![],
+            creates: vec![],
+        })
+    }
+
+    #[test]
+    fn extension_sql_schema_placeholder() {
+        let util = || {
+            SqlGraphEntity::Schema(SchemaEntity {
+                module_path: "pretty::util",
+                name: "util",
+                file: "src/util.rs",
+                line: 1,
+            })
+        };
+        let mut relocatable = extension_root();
+        if let SqlGraphEntity::ExtensionRoot(control) = &mut relocatable {
+            control.relocatable = true;
+        }
+
+        let graph = |root: SqlGraphEntity, sql: SqlGraphEntity| vec![root, util(), sql];
+        for (entities, expected) in [
+            (
+                graph(
+                    extension_root(),
+                    schema_placeholder_sql("pretty::util", false),
+                ),
+                "CREATE TABLE util.settings",
+            ),
+            // outside of any schema module, even when connected to one through `bootstrap`, or
+            // when the extension is relocatable
+            (
+                graph(extension_root(), schema_placeholder_sql("pretty", false)),
+                "CREATE TABLE @extschema@.settings",
+            ),
+            (
+                graph(extension_root(), schema_placeholder_sql("pretty", true)),
+                "CREATE TABLE @extschema@.settings",
+            ),
+            (
+                graph(relocatable, schema_placeholder_sql("pretty", false)),
+                "CREATE TABLE @extschema@.settings",
+            ),
+        ] {
+            let sql = build(entities).to_sql().expect("failed to generate SQL");
+            assert!(sql.contains(expected), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn extern_leakproof() {
+        let mut leakproof = i32_function("leakproof", "pretty::leakproof", 1);
+        if let SqlGraphEntity::Function(func) = &mut leakproof {
+            func.extern_attrs.push(ExternArgs::Leakproof);
+        }
+
+        let sql = build(vec![extension_root(), leakproof])
+            .to_sql()
+            .expect("failed to generate SQL");
+        assert!(sql.contains("IMMUTABLE LEAKPROOF"), "{}", sql);
+    }
+
+    #[test]
+    fn extern_window() {
+        let mut window = i32_function("window", "pretty::window", 1);
+        if let SqlGraphEntity::Function(func) = &mut window {
+            func.extern_attrs.push(ExternArgs::Window);
+        }
+
+        let sql = build(vec![extension_root(), window])
+            .to_sql()
+            .expect("failed to generate SQL");
+        assert!(sql.contains("IMMUTABLE WINDOW"), "{}", sql);
+    }
+
+    #[test]
+    fn extern_inout() {
+        let mut swap = i32_function("swap", "pretty::swap", 1);
+        if let SqlGraphEntity::Function(func) = &mut swap {
+            for arg in &mut func.fn_args {
+                arg.is_inout = true;
+            }
+            func.fn_return = PgExternReturnEntity::Iterated(vec![
+                (TypeId::of::<i32>(), "i32", "i32", String::from(""), None),
+                (TypeId::of::<i32>(), "i32", "i32", String::from(""), None),
+            ]);
+        }
+
+        let sql = build(vec![extension_root(), swap])
+            .to_sql()
+            .expect("failed to generate SQL");
+        assert!(sql.contains("\t\"a\" INOUT integer, /* i32 */"), "{}", sql);
+        assert!(sql.contains("\t\"b\" INOUT integer /* i32 */"), "{}", sql);
+        assert!(sql.contains(") RETURNS record\n"), "{}", sql);
+    }
+
+    #[test]
+    fn extern_procedure() {
+        let mut load = i32_function("load", "pretty::load", 1);
+        if let SqlGraphEntity::Function(func) = &mut load {
+            func.extern_attrs = vec![ExternArgs::Procedure];
+            func.fn_return = PgExternReturnEntity::None;
+        }
+
+        let sql = build(vec![extension_root(), load])
+            .to_sql()
+            .expect("failed to generate SQL");
+        assert!(
+            sql.contains(
+                "CREATE OR REPLACE PROCEDURE \"load\"(\n\
+                 \t\"a\" integer, /* i32 */\n\
+                 \t\"b\" integer /* i32 */\n\
+                 )\n\
+                 LANGUAGE c /* Rust */\n\
+                 AS 'MODULE_PATHNAME', 'load_wrapper';"
+            ),
+            "{}",
+            sql
+        );
+    }
+
+    #[test]
+    fn extern_procedure_unsupported_attr() {
+        let mut load = i32_function("load", "pretty::load", 1);
+        if let SqlGraphEntity::Function(func) = &mut load {
+            func.extern_attrs = vec![ExternArgs::Procedure, ExternArgs::Immutable];
+            func.fn_return = PgExternReturnEntity::None;
+        }
+
+        let error = build(vec![extension_root(), load])
+            .to_sql()
+            .expect_err("created an immutable procedure");
+        assert_eq!(
+            error.to_string(),
+            "Procedure `pretty::load` can't be created with `Immutable`"
+        );
+    }
+
+    #[test]
+    fn extern_requires_unknown_target() {
+        let mut uses_table = i32_function("uses_table", "pretty::uses_table", 1);
+        if let SqlGraphEntity::Function(func) = &mut uses_table {
+            func.extern_attrs
+                .push(ExternArgs::Requires(vec![PositioningRef::Name(
+                    String::from("no_such_sql"),
+                )]));
+        }
+
+        let error = PgxSql::build(
+            DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(),
+            DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(),
+            vec![extension_root(), uses_table].into_iter(),
+        )
+        .err()
+        .expect("an unknown `requires` target was accepted");
+        assert!(error.to_string().contains("no_such_sql"), "{}", error);
+    }
+
+    #[test]
+    fn unmappable_types_are_reported() {
+        struct Unsupported;
+
+        let mut takes_unsupported =
+            i32_function("takes_unsupported", "pretty::takes_unsupported", 1);
+        if let SqlGraphEntity::Function(func) = &mut takes_unsupported {
+            func.fn_args.push(PgExternArgumentEntity {
+                pattern: "thing",
+                ty_source: "Unsupported",
+                ty_id: TypeId::of::<Unsupported>(),
+                full_path: "pretty::Unsupported",
+                module_path: String::from("pretty"),
+                is_optional: false,
+                is_variadic: false,
+                is_inout: false,
+                default: None,
+            });
+            func.fn_return = PgExternReturnEntity::SetOf {
+                id: TypeId::of::<Unsupported>(),
+                source: "Unsupported",
+                full_path: "pretty::Unsupported",
+                module_path: String::from("pretty"),
+            };
+        }
+
+        let error = PgxSql::build(
+            DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(),
+            DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(),
+            vec![
+                extension_root(),
+                takes_unsupported,
+                i32_function("add", "pretty::add", 10),
+            ]
+            .into_iter(),
+        )
+        .err()
+        .expect("an unmappable type was accepted");
+        assert_eq!(
+            "Failed to map 2 type(s) to SQL:\n\
+             \t`pretty::takes_unsupported` argument `thing`: `pretty::Unsupported`\n\
+             \t`pretty::takes_unsupported` returns: `pretty::Unsupported`",
+            error.to_string()
+        );
+    }
+
+    fn color_enum() -> SqlGraphEntity {
+        SqlGraphEntity::Enum(PostgresEnumEntity {
+            name: "Color",
+            file: "src/colors.rs",
+            line: 3,
+            full_path: "pretty::colors::Color",
+            module_path: "pretty::colors",
+            mappings: Default::default(),
+            variants: vec!["Red", "Green"],
+        })
+    }
+
+    #[test]
+    fn create_type_if_not_exists() {
+        let mut pgx_sql = build(vec![extension_root(), color_enum()]);
+
+        let sql = pgx_sql.to_sql().expect("failed to generate SQL");
+        assert!(
+            sql.contains("\nCREATE TYPE Color AS ENUM (\n\t'Red',\n\t'Green'\n);"),
+            "{}",
+            sql
+        );
+        assert!(!sql.contains("DO $$"), "{}", sql);
+
+        pgx_sql.if_not_exists = true;
+        let sql = pgx_sql.to_sql().expect("failed to generate SQL");
+        assert!(
+            sql.contains(concat!(
+                "-- pretty::colors::Color\n",
+                "DO $$ BEGIN\n",
+                "\tCREATE TYPE Color AS ENUM (\n",
+                "\t\t'Red',\n",
+                "\t\t'Green'\n",
+                "\t);\n",
+                "EXCEPTION WHEN duplicate_object THEN NULL;\n",
+                "END $$;\n",
+            )),
+            "{}",
+            sql
+        );
+    }
+
+    #[test]
+    fn drop_stale_function_signatures() {
+        let mut add = i32_function("add", "pretty::add", 1);
+        if let SqlGraphEntity::Function(func) = &mut add {
+            func.fn_args[0].ty_source = "i64";
+            func.fn_args[0].ty_id = TypeId::of::<i64>();
+            func.fn_args[0].full_path = "i64";
+        }
+        // an overload that's unchanged, and should stay
+        let mut add_i32 = i32_function("add", "pretty::add_i32", 2);
+        if let SqlGraphEntity::Function(func) = &mut add_i32 {
+            func.fn_args.pop();
+        }
+        let unchanged = i32_function("unchanged", "pretty::unchanged", 3);
+        let mut pgx_sql = build(vec![extension_root(), add, add_i32, unchanged]);
+
+        assert_eq!(
+            pgx_sql
+                .function_manifest()
+                .expect("failed to build manifest"),
+            "\"add\"(bigint, integer)\n\"add\"(integer)\n\"unchanged\"(integer, integer)\n"
+        );
+        let sql = pgx_sql.to_sql().expect("failed to generate SQL");
+        assert!(!sql.contains("DROP FUNCTION"), "{}", sql);
+
+        pgx_sql.previous_functions = FunctionSignature::parse_manifest(
+            "\"add\"(integer, integer)\n\"add\"(integer)\n\"unchanged\"(integer, integer)\n",
+        )
+        .expect("failed to parse manifest");
+        let sql = pgx_sql.to_sql().expect("failed to generate SQL");
+        assert_eq!(sql.matches("DROP FUNCTION").count(), 1, "{}", sql);
+        assert!(
+            sql.contains(
+                "-- pretty::add\n\
+                 DROP FUNCTION IF EXISTS \"add\"(integer, integer);\n\
+                 CREATE OR REPLACE FUNCTION \"add\"(\n\
+                 \t\"a\" bigint, /* i64 */"
+            ),
+            "{}",
+            sql
+        );
+    }
+
+    #[test]
+    fn function_manifest_file_round_trip() {
+        let manifest =
+            std::env::temp_dir().join(format!("pgx-function-manifest-{}.txt", std::process::id()));
+        let manifest = manifest.to_str().expect("temp dir isn't UTF-8");
+
+        let previous = build(vec![
+            extension_root(),
+            i32_function("add", "pretty::add", 1),
+        ]);
+        previous
+            .function_manifest_to_file(manifest)
+            .expect("failed to write manifest");
+
+        let mut add = i32_function("add", "pretty::add", 1);
+        if let SqlGraphEntity::Function(func) = &mut add {
+            func.fn_args[0].ty_source = "i64";
+            func.fn_args[0].ty_id = TypeId::of::<i64>();
+            func.fn_args[0].full_path = "i64";
+        }
+        let mut pgx_sql = build(vec![extension_root(), add]);
+        let loaded = pgx_sql.previous_functions_from_file(manifest);
+        std::fs::remove_file(manifest).expect("failed to remove manifest");
+        loaded.expect("failed to read manifest");
+
+        assert_eq!(
+            pgx_sql.previous_functions,
+            vec![FunctionSignature {
+                schema: None,
+                name: String::from("add"),
+                arguments: vec![String::from("integer"), String::from("integer")],
+            }]
+        );
+        let sql = pgx_sql.to_sql().expect("failed to generate SQL");
+        assert!(
+            sql.contains("DROP FUNCTION IF EXISTS \"add\"(integer, integer);\n"),
+            "{}",
+            sql
+        );
+    }
+
+    #[test]
+    fn schemas_declared_twice_are_created_once() {
+        let mut entities = vec![extension_root()];
+        for module_path in ["pretty::a::util", "pretty::b::util"] {
+            entities.push(SqlGraphEntity::Schema(SchemaEntity {
+                module_path,
+                name: "util",
+                file: "src/lib.rs",
+                line: 1,
+            }));
+        }
+        let mut first = i32_function("first", "pretty::a::util::first", 10);
+        if let SqlGraphEntity::Function(func) = &mut first {
+            func.module_path = "pretty::a::util";
+        }
+        let mut second = i32_function("second", "pretty::b::util::second", 20);
+        if let SqlGraphEntity::Function(func) = &mut second {
+            func.module_path = "pretty::b::util";
+        }
+        entities.push(first);
+        entities.push(second);
+
+        let sql = build(entities).to_sql().expect("failed to generate SQL");
+        assert_eq!(sql.matches("CREATE SCHEMA").count(), 1, "{}", sql);
+        let create_schema = sql.find("CREATE SCHEMA IF NOT EXISTS util;").unwrap();
+        for name in ["\"first\"", "\"second\""] {
+            let function = sql.find(name).expect("missing function");
+            assert!(create_schema < function, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn to_sql_pretty() {
+        let entities = vec![
+            extension_root(),
+            i32_function("subtract", "pretty::subtract", 20),
+            i32_function("add", "pretty::add", 10),
+            color_enum(),
+            SqlGraphEntity::Schema(SchemaEntity {
+                module_path: "pretty::colors",
+                name: "colors",
+                file: "src/colors.rs",
+                line: 1,
+            }),
+        ];
+
+        let sql = build(entities)
+            .to_sql_pretty()
+            .expect("failed to generate SQL");
+
+        let expected = concat!(
+            "/* \n",
+            "This file is auto generated by pgx.\n",
+            "\n",
+            "Items are grouped by kind, and ordered by their dependencies and then by where they're\n",
+            "declared, so the ordering is stable.\n",
+            "*/\n",
+            "\n",
+            "-- ------------------------------------------------------------\n",
+            "-- Schemas\n",
+            "-- ------------------------------------------------------------\n",
+            "\n",
+            "-- src/colors.rs:1\n",
+            "CREATE SCHEMA IF NOT EXISTS colors; /* pretty::colors */\n",
+            "\n",
+            "-- ------------------------------------------------------------\n",
+            "-- Types\n",
+            "-- ------------------------------------------------------------\n",
+            "\n",
+            "-- src/colors.rs:3\n",
+            "-- pretty::colors::Color\n",
+            "CREATE TYPE colors.Color AS ENUM (\n",
+            "\t'Red',\n",
+            "\t'Green'\n",
+            ");\n",
+            "\n",
+            "-- ------------------------------------------------------------\n",
+            "-- Functions\n",
+            "-- ------------------------------------------------------------\n",
+            "\n",
+            "-- src/lib.rs:10\n",
+            "-- pretty::add\n",
+            "CREATE OR REPLACE FUNCTION \"add\"(\n",
+            "\t\"a\" integer, /* i32 */\n",
+            "\t\"b\" integer /* i32 */\n",
+            ") RETURNS integer /* i32 */\n",
+            "IMMUTABLE STRICT\n",
+            "LANGUAGE c /* Rust */\n",
+            "AS 'MODULE_PATHNAME', 'add_wrapper';\n",
+            "\n",
+            "-- src/lib.rs:20\n",
+            "-- pretty::subtract\n",
+            "CREATE OR REPLACE FUNCTION \"subtract\"(\n",
+            "\t\"a\" integer, /* i32 */\n",
+            "\t\"b\" integer /* i32 */\n",
+            ") RETURNS integer /* i32 */\n",
+            "IMMUTABLE STRICT\n",
+            "LANGUAGE c /* Rust */\n",
+            "AS 'MODULE_PATHNAME', 'subtract_wrapper';\n",
+        );
+        assert_eq!(expected, sql);
+    }
+}
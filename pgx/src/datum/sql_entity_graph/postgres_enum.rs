@@ -72,18 +72,11 @@ impl ToSql for PostgresEnumEntity {
     #[tracing::instrument(level = "debug", err, skip(self, context), fields(identifier = %self.rust_identifier()))]
     fn to_sql(&self, context: &super::PgxSql) -> eyre::Result<String> {
         let self_index = context.enums[self];
-        let sql = format!(
-            "\n\
-                    -- {file}:{line}\n\
-                    -- {full_path}\n\
-                    CREATE TYPE {schema}{name} AS ENUM (\n\
-                        {variants}\
-                    );\
-                ",
+        let create_type = format!(
+            "CREATE TYPE {schema}{name} AS ENUM (\n\
+                {variants}\
+            );",
             schema = context.schema_prefix_for(&self_index),
-            full_path = self.full_path,
-            file = self.file,
-            line = self.line,
             name = self.name,
             variants = self
                 .variants
@@ -93,6 +86,17 @@ impl ToSql for PostgresEnumEntity {
                 .join(",\n")
                 + "\n",
         );
+        let sql = format!(
+            "\n\
+                    -- {file}:{line}\n\
+                    -- {full_path}\n\
+                    {create_type}\
+                ",
+            full_path = self.full_path,
+            file = self.file,
+            line = self.line,
+            create_type = context.create_type_sql(&create_type),
+        );
         tracing::debug!(%sql);
         Ok(sql)
     }
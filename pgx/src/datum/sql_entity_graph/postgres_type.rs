@@ -20,6 +20,10 @@ pub struct PostgresTypeEntity {
     pub in_fn_module_path: String,
     pub out_fn: &'static str,
     pub out_fn_module_path: String,
+    pub storage: Option<&'static str>,
+    pub alignment: Option<&'static str>,
+    /// The size of a fixed-length type, in bytes, or `None` for a varlena type.
+    pub internal_length: Option<usize>,
 }
 
 impl crate::PostgresType for PostgresTypeEntity {}
@@ -164,29 +168,26 @@ impl ToSql for PostgresTypeEntity {
             "\n\
                                 -- {file}:{line}\n\
                                 -- {full_path}\n\
-                                CREATE TYPE {schema}{name};\
+                                {create_type}\
                             ",
-            schema = context.schema_prefix_for(&self_index),
             full_path = item.full_path,
             file = item.file,
             line = item.line,
-            name = item.name,
+            create_type = context.create_type_sql(&format!(
+                "CREATE TYPE {schema}{name};",
+                schema = context.schema_prefix_for(&self_index),
+                name = item.name,
+            )),
         );
         tracing::debug!(sql = %shell_type);
 
-        let materialized_type = format!("\n\
-                                -- {file}:{line}\n\
-                                -- {full_path}\n\
-                                CREATE TYPE {schema}{name} (\n\
-                                    \tINTERNALLENGTH = variable,\n\
+        let create_type = format!("CREATE TYPE {schema}{name} (\n\
+                                    \tINTERNALLENGTH = {internal_length},\n\
                                     \tINPUT = {schema_prefix_in_fn}{in_fn}, /* {in_fn_path} */\n\
                                     \tOUTPUT = {schema_prefix_out_fn}{out_fn}, /* {out_fn_path} */\n\
-                                    \tSTORAGE = extended\n\
-                                );\
-                            ",
-                                        full_path = item.full_path,
-                                        file = item.file,
-                                        line = item.line,
+                                    {alignment}\
+                                    \tSTORAGE = {storage}\n\
+                                );",
                                         schema = context.schema_prefix_for(&self_index),
                                         name = item.name,
                                         schema_prefix_in_fn = context.schema_prefix_for(&in_fn_graph_index),
@@ -195,6 +196,20 @@ impl ToSql for PostgresTypeEntity {
                                         schema_prefix_out_fn = context.schema_prefix_for(&out_fn_graph_index),
                                         out_fn = item.out_fn,
                                         out_fn_path = out_fn_path,
+                                        alignment = item.alignment.map(|alignment| format!("\tALIGNMENT = {},\n", alignment)).unwrap_or_default(),
+                                        internal_length = item.internal_length.map(|length| length.to_string()).unwrap_or_else(|| String::from("variable")),
+                                        storage = item.storage.unwrap_or(if item.internal_length.is_some() { "plain" } else { "extended" }),
+        );
+        let materialized_type = format!(
+            "\n\
+                                -- {file}:{line}\n\
+                                -- {full_path}\n\
+                                {create_type}\
+                            ",
+            full_path = item.full_path,
+            file = item.file,
+            line = item.line,
+            create_type = context.create_type_sql(&create_type),
         );
         tracing::debug!(sql = %materialized_type);
 
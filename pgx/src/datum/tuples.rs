@@ -1,107 +1,111 @@
 // Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
 // governed by the MIT license that can be found in the LICENSE file.
 
-use crate::{pg_sys, FromDatum, IntoDatum};
+//! Tuples of 2 to 5 `Option`s convert to and from anonymous `record`s, with one field per element
+//! and a `None` element as a NULL field
+use crate::{heap_tuple_get_datum, pg_sys, FromDatum, IntoDatum, PgMemoryContexts, PgTupleDesc};
 
-impl<A, B> IntoDatum for (Option<A>, Option<B>)
-where
-    A: IntoDatum,
-    B: IntoDatum,
-{
-    fn into_datum(self) -> Option<pg_sys::Datum> {
-        let vec = vec![self.0.into_datum(), self.1.into_datum()];
-        vec.into_datum()
-    }
+/// Build an anonymous `record` whose fields, named `f1`, `f2`, ..., are of the types `typoids`
+unsafe fn record_into_datum(
+    typoids: &[pg_sys::Oid],
+    values: &[Option<pg_sys::Datum>],
+) -> Option<pg_sys::Datum> {
+    #[cfg(any(feature = "pg10", feature = "pg11"))]
+    let tupdesc = pg_sys::CreateTemplateTupleDesc(typoids.len() as i32, false);
+    #[cfg(any(feature = "pg12", feature = "pg13", feature = "pg14"))]
+    let tupdesc = pg_sys::CreateTemplateTupleDesc(typoids.len() as i32);
 
-    fn type_oid() -> pg_sys::Oid {
-        0
+    for (i, typoid) in typoids.iter().enumerate() {
+        let name = std::ffi::CString::new(format!("f{}", i + 1)).unwrap();
+        pg_sys::TupleDescInitEntry(
+            tupdesc,
+            (i + 1) as pg_sys::AttrNumber,
+            name.as_ptr(),
+            *typoid,
+            -1,
+            0,
+        );
     }
-}
 
-impl<A, B, C> IntoDatum for (Option<A>, Option<B>, Option<C>)
-where
-    A: IntoDatum,
-    B: IntoDatum,
-    C: IntoDatum,
-{
-    fn into_datum(self) -> Option<pg_sys::Datum> {
-        let vec = vec![
-            self.0.into_datum(),
-            self.1.into_datum(),
-            self.2.into_datum(),
-        ];
-        vec.into_datum()
-    }
+    // register the row type, so that the record can be read back from just its Datum
+    let tupdesc = pg_sys::BlessTupleDesc(tupdesc);
 
-    fn type_oid() -> pg_sys::Oid {
-        0
-    }
+    let mut datums = values
+        .iter()
+        .map(|value| value.unwrap_or(0))
+        .collect::<Vec<_>>();
+    let mut nulls = values.iter().map(Option::is_none).collect::<Vec<_>>();
+    let heap_tuple = pg_sys::heap_form_tuple(tupdesc, datums.as_mut_ptr(), nulls.as_mut_ptr());
+    Some(heap_tuple_get_datum(heap_tuple))
 }
 
-impl<A, B> FromDatum for (Option<A>, Option<B>)
-where
-    A: FromDatum + IntoDatum,
-    B: FromDatum + IntoDatum,
-{
-    const NEEDS_TYPID: bool = A::NEEDS_TYPID || B::NEEDS_TYPID;
-    unsafe fn from_datum(datum: pg_sys::Datum, is_null: bool, typoid: pg_sys::Oid) -> Option<Self>
-    where
-        Self: Sized,
-    {
-        let mut vec = Vec::<Option<pg_sys::Datum>>::from_datum(datum, is_null, typoid).unwrap();
-        let b = vec.pop().unwrap();
-        let a = vec.pop().unwrap();
+macro_rules! impl_tuple_datum {
+    ($len:literal, $($t:ident: $idx:tt),+) => {
+        impl<$($t),+> IntoDatum for ($(Option<$t>,)+)
+        where
+            $($t: IntoDatum,)+
+        {
+            fn into_datum(self) -> Option<pg_sys::Datum> {
+                let typoids = [$($t::type_oid()),+];
+                let values = [$(self.$idx.into_datum()),+];
+                unsafe { record_into_datum(&typoids, &values) }
+            }
 
-        let a_datum = if a.is_some() {
-            A::from_datum(a.unwrap(), false, A::type_oid())
-        } else {
-            None
-        };
+            fn type_oid() -> pg_sys::Oid {
+                pg_sys::RECORDOID
+            }
+        }
 
-        let b_datum = if b.is_some() {
-            B::from_datum(b.unwrap(), false, B::type_oid())
-        } else {
-            None
-        };
+        impl<$($t),+> FromDatum for ($(Option<$t>,)+)
+        where
+            $($t: FromDatum,)+
+        {
+            const NEEDS_TYPID: bool = false;
 
-        Some((a_datum, b_datum))
-    }
-}
+            unsafe fn from_datum(
+                datum: pg_sys::Datum,
+                is_null: bool,
+                _typoid: pg_sys::Oid,
+            ) -> Option<Self> {
+                if is_null {
+                    return None;
+                } else if datum == 0 {
+                    panic!("a record Datum was flagged as non-null but the datum is zero");
+                }
 
-impl<A, B, C> FromDatum for (Option<A>, Option<B>, Option<C>)
-where
-    A: FromDatum + IntoDatum,
-    B: FromDatum + IntoDatum,
-    C: FromDatum + IntoDatum,
-{
-    const NEEDS_TYPID: bool = A::NEEDS_TYPID || B::NEEDS_TYPID || C::NEEDS_TYPID;
-    unsafe fn from_datum(datum: pg_sys::Datum, is_null: bool, typoid: pg_sys::Oid) -> Option<Self>
-    where
-        Self: Sized,
-    {
-        let mut vec = Vec::<Option<pg_sys::Datum>>::from_datum(datum, is_null, typoid).unwrap();
-        let c = vec.pop().unwrap();
-        let b = vec.pop().unwrap();
-        let a = vec.pop().unwrap();
+                let tupdesc = PgTupleDesc::from_composite(datum);
+                if tupdesc.len() != $len {
+                    panic!(
+                        "expected a record with {} fields, but it has {}",
+                        $len,
+                        tupdesc.len()
+                    );
+                }
+                Some(($(tupdesc.get_attr::<$t>($idx),)+))
+            }
 
-        let a_datum = if a.is_some() {
-            A::from_datum(a.unwrap(), false, A::type_oid())
-        } else {
-            None
-        };
-
-        let b_datum = if b.is_some() {
-            B::from_datum(b.unwrap(), false, B::type_oid())
-        } else {
-            None
-        };
-
-        let c_datum = if c.is_some() {
-            C::from_datum(c.unwrap(), false, C::type_oid())
-        } else {
-            None
-        };
-
-        Some((a_datum, b_datum, c_datum))
-    }
+            unsafe fn from_datum_in_memory_context(
+                mut memory_context: PgMemoryContexts,
+                datum: pg_sys::Datum,
+                is_null: bool,
+                typoid: pg_sys::Oid,
+            ) -> Option<Self> {
+                if is_null {
+                    None
+                } else {
+                    // copy the whole record, so that borrowed fields (such as a `&str`) point
+                    // into memory owned by `memory_context`
+                    memory_context.switch_to(|_| {
+                        let copy = pg_sys::pg_detoast_datum_copy(datum as *mut pg_sys::varlena);
+                        Self::from_datum(copy as pg_sys::Datum, false, typoid)
+                    })
+                }
+            }
+        }
+    };
 }
+
+impl_tuple_datum!(2, A: 0, B: 1);
+impl_tuple_datum!(3, A: 0, B: 1, C: 2);
+impl_tuple_datum!(4, A: 0, B: 1, C: 2, D: 3);
+impl_tuple_datum!(5, A: 0, B: 1, C: 2, D: 3, E: 4);
@@ -0,0 +1,75 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+use crate::{ereport, pg_sys, FromDatum, IntoDatum, PgLogLevel, PgSqlErrorCode};
+use std::ops::Deref;
+
+/// A Postgres `varchar`, for `#[pg_extern]` functions whose arguments or return value must be
+/// `varchar` rather than the `text` that `&str` and `String` map to.
+///
+/// Postgres discards any length given to a function's argument or return type, so the length of a
+/// `varchar(n)` can only be enforced by the function itself, via [`Varchar::with_max_length`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Varchar(pub String);
+
+impl Varchar {
+    /// A `varchar(max_length)`, raising the same ERROR Postgres would if `value` is longer than
+    /// `max_length` characters
+    pub fn with_max_length(value: impl Into<String>, max_length: usize) -> Self {
+        let value = value.into();
+        if value.chars().count() > max_length {
+            ereport(
+                PgLogLevel::ERROR,
+                PgSqlErrorCode::ERRCODE_STRING_DATA_RIGHT_TRUNCATION,
+                &format!("value too long for type character varying({})", max_length),
+                file!(),
+                line!(),
+                column!(),
+            );
+        }
+        Varchar(value)
+    }
+}
+
+impl Deref for Varchar {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromDatum for Varchar {
+    const NEEDS_TYPID: bool = false;
+    #[inline]
+    unsafe fn from_datum(
+        datum: pg_sys::Datum,
+        is_null: bool,
+        typoid: pg_sys::Oid,
+    ) -> Option<Varchar> {
+        String::from_datum(datum, is_null, typoid).map(Varchar)
+    }
+}
+
+impl IntoDatum for Varchar {
+    #[inline]
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        self.0.into_datum()
+    }
+
+    fn type_oid() -> u32 {
+        pg_sys::VARCHAROID
+    }
+}
+
+impl From<String> for Varchar {
+    fn from(value: String) -> Self {
+        Varchar(value)
+    }
+}
+
+impl From<&str> for Varchar {
+    fn from(value: &str) -> Self {
+        Varchar(value.to_string())
+    }
+}
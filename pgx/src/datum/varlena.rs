@@ -175,6 +175,38 @@ where
         }
     }
 
+    /// Create a new `PgVarlena` holding a copy of this one's value, as changed by `f`.  `self`
+    /// is left untouched.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// use pgx::PgVarlena;
+    /// #[derive(Copy, Clone)]
+    /// struct State {
+    ///    sum: i32,
+    ///    n: i32,
+    /// }
+    ///
+    /// fn acc(state: PgVarlena<State>, v: i32) -> PgVarlena<State> {
+    ///     state.modify(|s| {
+    ///         s.sum += v;
+    ///         s.n += 1;
+    ///     })
+    /// }
+    /// ```
+    pub fn modify<F: FnOnce(&mut T)>(&self, f: F) -> PgVarlena<T> {
+        let mut new = PgVarlena::<T>::new();
+        *new = *self.as_ref();
+        f(&mut new);
+        new
+    }
+
+    /// Copy the Rust value out of this `PgVarlena`
+    pub fn into_inner(self) -> T {
+        *self.as_ref()
+    }
+
     /// Use when you need to pass the backing `*mut pg_sys::varlena` to a Postgres function.
     ///
     /// This method is also used by the `IntoDatum for PgVarlena<T> where T: Copy + Sized`
@@ -5,7 +5,9 @@
 //!
 //! Other than the exported macros, typically these functions are not necessary to call directly
 //! as they're used behind the scenes by the code generated by the `#[pg_extern]` macro.
-use crate::{pg_sys, void_mut_ptr, AllocatedByRust, FromDatum, PgBox, PgMemoryContexts};
+use crate::{
+    heap_tuple_get_datum, pg_sys, void_mut_ptr, AllocatedByRust, FromDatum, PgBox, PgMemoryContexts,
+};
 
 /// A macro for specifying default argument values so they get propery translated to SQL in
 /// `CREATE FUNCTION` statements
@@ -73,8 +75,47 @@ macro_rules! name {
     };
 }
 
+/// Declare a `#[pg_extern]` argument as `VARIADIC`
+///
+/// `variadic!(T)` is the element type, and the argument itself is a [`VariadicArray<T>`](crate::VariadicArray)
+/// holding however many trailing arguments the caller provided.
+///
+/// ```rust
+/// use pgx::*;
+///
+/// #[pg_extern]
+/// fn concat_all(parts: variadic!(&str)) -> String {
+///     parts.iter().flatten().collect()
+/// }
+/// ```
+///
+/// Generates `CREATE FUNCTION concat_all(VARIADIC text[])`, callable as `concat_all('a', 'b', 'c')`.
 #[macro_export]
 macro_rules! variadic {
+    ($ty:ty) => {
+        $crate::VariadicArray<$ty>
+    };
+}
+
+/// Declare a `#[pg_extern]` argument as `INOUT`
+///
+/// The argument is passed in as a plain `T`, and the function returns its new value.  With more
+/// than one `INOUT` argument, the function returns a tuple of their new values, in the order of
+/// the arguments, which becomes the `record` Postgres returns.
+///
+/// ```rust
+/// use pgx::*;
+///
+/// #[pg_extern]
+/// fn swap(a: inout!(i32), b: inout!(i32)) -> (i32, i32) {
+///     (b, a)
+/// }
+/// ```
+///
+/// Generates `CREATE FUNCTION swap(a INOUT integer, b INOUT integer) RETURNS record`, whose
+/// fields are read back with `SELECT a, b FROM swap(1, 2)`.
+#[macro_export]
+macro_rules! inout {
     ($ty:ty) => {
         $ty
     };
@@ -100,7 +141,8 @@ mod pg_10_11 {
 
     #[inline]
     pub fn pg_arg_is_null(fcinfo: pg_sys::FunctionCallInfo, num: usize) -> bool {
-        unsafe { fcinfo.as_ref() }.unwrap().argnull[num] as bool
+        let fcinfo = unsafe { fcinfo.as_ref() }.unwrap();
+        fcinfo.argnull[..fcinfo.nargs as usize][num] as bool
     }
 
     #[inline]
@@ -165,8 +207,7 @@ mod pg_12_13_14 {
         let fcinfo = unsafe { fcinfo.as_mut() }.unwrap();
         unsafe {
             let nargs = fcinfo.nargs;
-            let len = std::mem::size_of::<pg_sys::NullableDatum>() * nargs as usize;
-            fcinfo.args.as_slice(len)[num]
+            fcinfo.args.as_slice(nargs as usize)[num]
         }
     }
 
@@ -206,6 +247,57 @@ pub unsafe fn get_getarg_type(fcinfo: pg_sys::FunctionCallInfo, num: usize) -> p
     pg_sys::get_fn_expr_argtype(fcinfo.as_ref().unwrap().flinfo, num as std::os::raw::c_int)
 }
 
+/// The collation of the function's input, as with Postgres' `PG_GET_COLLATION()` macro.
+///
+/// This is the collation Postgres resolved for the call (from its arguments, or an explicit
+/// `COLLATE` clause), or `pg_sys::InvalidOid` if none of its arguments are of a collatable type.
+/// Collation-sensitive functions can get at it by taking a `fcinfo: pg_sys::FunctionCallInfo`
+/// as their last argument.
+///
+/// # Safety
+///
+/// The provided `fcinfo` must be valid otherwise this function results in undefined behavior due
+/// to an out of bounds read.
+#[inline]
+pub unsafe fn pg_get_collation(fcinfo: pg_sys::FunctionCallInfo) -> pg_sys::Oid {
+    fcinfo.as_ref().unwrap().fncollation
+}
+
+/// The memory context of the aggregate this function is being called by, as with Postgres'
+/// `AggCheckCallContext()`, or `None` if it isn't being called as part of an aggregate.
+///
+/// An aggregate's transition state must be allocated here, so that it lasts from one row to
+/// the next.
+///
+/// # Safety
+///
+/// The provided `fcinfo` must be valid otherwise this function results in undefined behavior due
+/// to an out of bounds read.
+#[inline]
+pub unsafe fn pg_agg_context(fcinfo: pg_sys::FunctionCallInfo) -> Option<PgMemoryContexts> {
+    let mut aggcontext = std::ptr::null_mut();
+    if pg_sys::AggCheckCallContext(fcinfo, &mut aggcontext) == 0 {
+        None
+    } else {
+        Some(PgMemoryContexts::For(aggcontext))
+    }
+}
+
+/// The number of arguments the function was called with, as with Postgres' `PG_NARGS()` macro.
+///
+/// Together with [`pg_arg_is_null`], this lets a non-strict function that takes a
+/// `fcinfo: pg_sys::FunctionCallInfo` as its last argument tell which of its arguments were
+/// actually NULL, which is otherwise only expressed by taking them as an `Option<T>`.
+///
+/// # Safety
+///
+/// The provided `fcinfo` must be valid otherwise this function results in undefined behavior due
+/// to an out of bounds read.
+#[inline]
+pub unsafe fn pg_nargs(fcinfo: pg_sys::FunctionCallInfo) -> usize {
+    fcinfo.as_ref().unwrap().nargs as usize
+}
+
 /// this is intended for Postgres functions that take an actual `cstring` argument, not for getting
 /// a varlena argument type as a CStr.
 #[inline]
@@ -224,6 +316,44 @@ pub fn pg_return_void() -> pg_sys::Datum {
     0 as pg_sys::Datum
 }
 
+/// Return `values` as the `record` a function with `OUT` or `INOUT` parameters returns, with one
+/// value per parameter.  A `None` value is a NULL field.
+///
+/// This is what `#[pg_extern]` returns the tuple of a function with `inout!()` arguments as.
+///
+/// # Safety
+///
+/// The provided `fcinfo` must be valid otherwise this function results in undefined behavior due
+/// to an out of bounds read.
+pub unsafe fn pg_return_record(
+    fcinfo: pg_sys::FunctionCallInfo,
+    values: &[Option<pg_sys::Datum>],
+) -> pg_sys::Datum {
+    let mut tupdesc = std::ptr::null_mut();
+    if pg_sys::get_call_result_type(fcinfo, std::ptr::null_mut(), &mut tupdesc)
+        != pg_sys::TypeFuncClass_TYPEFUNC_COMPOSITE
+    {
+        panic!("function returning record called in context that cannot accept type record");
+    }
+    let tupdesc = pg_sys::BlessTupleDesc(tupdesc);
+    let natts = (*tupdesc).natts as usize;
+    if natts != values.len() {
+        panic!(
+            "the function's record has {} fields, but {} values were returned",
+            natts,
+            values.len()
+        );
+    }
+
+    let mut datums = values
+        .iter()
+        .map(|value| value.unwrap_or(0))
+        .collect::<Vec<_>>();
+    let mut nulls = values.iter().map(Option::is_none).collect::<Vec<_>>();
+    let heap_tuple = pg_sys::heap_form_tuple(tupdesc, datums.as_mut_ptr(), nulls.as_mut_ptr());
+    heap_tuple_get_datum(heap_tuple)
+}
+
 /// Retrieve the `.flinfo.fn_extra` pointer (as a PgBox'd type) from [pg_sys::FunctionCallInfo].
 ///
 /// This function is unsafe as we cannot guarantee the provided [fcinfo] pointer is valid
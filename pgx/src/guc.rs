@@ -137,6 +137,30 @@ where
     }
 }
 
+/// Registers custom GUCs with Postgres, via its `DefineCustom*Variable()` functions.
+///
+/// This is usually done from the extension's `_PG_init()`, with each setting's current value
+/// then available from its `GucSetting::get()`:
+///
+/// ```rust,ignore
+/// static BATCH_SIZE: GucSetting<i32> = GucSetting::new(100);
+///
+/// #[pg_guard]
+/// pub extern "C" fn _PG_init() {
+///     GucRegistry::define_int_guc(
+///         "my_extension.batch_size",
+///         "rows per batch",
+///         "The number of rows my_extension processes at a time",
+///         &BATCH_SIZE,
+///         1,
+///         10_000,
+///         GucContext::Userset,
+///     );
+/// }
+/// ```
+///
+/// A value for the setting from `postgresql.conf`, or from a `SET` before the extension was
+/// loaded, takes effect once it's defined.
 pub struct GucRegistry {}
 impl GucRegistry {
     pub fn define_bool_guc(
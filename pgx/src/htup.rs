@@ -183,3 +183,72 @@ pub fn heap_getattr_datum_ex(
         typbyval,
     }
 }
+
+/// A `pg_sys::HeapTuple` formed from a set of attribute values, such as a row to be returned from a
+/// function as a composite type.
+///
+/// It's returned to Postgres as a `record`.
+pub struct PgHeapTuple {
+    tuple: pg_sys::HeapTuple,
+}
+
+impl PgHeapTuple {
+    /// Form a tuple, allocated in the `CurrentMemoryContext`, described by `tupdesc`.  `datums` and
+    /// `nulls` hold one entry per attribute of `tupdesc`, and the entry in `datums` is ignored
+    /// wherever `nulls` is `true`.
+    ///
+    /// `tupdesc` needs to describe a named composite type, such as one returned by
+    /// [`composite_type!()`](crate::composite_type), or have been blessed via
+    /// `pg_sys::BlessTupleDesc`.
+    ///
+    /// ## Safety
+    ///
+    /// This function is unsafe as it cannot validate that each `pg_sys::Datum` is of the type of its
+    /// corresponding attribute.
+    ///
+    /// ## Panics
+    ///
+    /// If `datums` or `nulls` don't have exactly as many entries as `tupdesc` has attributes
+    pub unsafe fn from_datums(
+        tupdesc: &PgTupleDesc,
+        datums: &[pg_sys::Datum],
+        nulls: &[bool],
+    ) -> Self {
+        assert_eq!(
+            tupdesc.len(),
+            datums.len(),
+            "wrong number of datums for the tuple descriptor"
+        );
+        assert_eq!(
+            tupdesc.len(),
+            nulls.len(),
+            "wrong number of nulls for the tuple descriptor"
+        );
+
+        // heap_form_tuple() doesn't modify these, it just isn't declared as taking const pointers
+        let mut datums = datums.to_vec();
+        let mut nulls = nulls.to_vec();
+        PgHeapTuple {
+            tuple: pg_sys::heap_form_tuple(
+                tupdesc.as_ptr(),
+                datums.as_mut_ptr(),
+                nulls.as_mut_ptr(),
+            ),
+        }
+    }
+
+    /// Return the wrapped `pg_sys::HeapTuple`
+    pub fn into_pg(self) -> pg_sys::HeapTuple {
+        self.tuple
+    }
+}
+
+impl IntoDatum for PgHeapTuple {
+    fn into_datum(self) -> Option<pg_sys::Datum> {
+        Some(heap_tuple_get_datum(self.tuple))
+    }
+
+    fn type_oid() -> pg_sys::Oid {
+        pg_sys::RECORDOID
+    }
+}
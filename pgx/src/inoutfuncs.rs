@@ -54,3 +54,128 @@ pub trait JsonInOutFuncs<'de>: serde::de::Deserialize<'de> + serde::ser::Seriali
         serde_json::to_writer(buffer, self).expect("failed to serialize to json")
     }
 }
+
+/// Splits and joins the fields of a custom type's text representation on a delimiter.
+///
+/// Any delimiter or backslash within a field is escaped with a backslash, so fields can contain
+/// the delimiter and still round-trip through [`DelimitedFields::join`] and
+/// [`DelimitedFields::split`].  This is meant for `input`/`output` functions of
+/// [`PgVarlenaInOutFuncs`] and [`InOutFuncs`] types:
+///
+/// ```rust
+/// use pgx::DelimitedFields;
+///
+/// let fields = DelimitedFields::new(',');
+/// let text = fields.join(&["Smith, John", "42"]);
+/// assert_eq!(text, r"Smith\, John,42");
+/// assert_eq!(fields.split(&text), vec!["Smith, John", "42"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelimitedFields {
+    delimiter: char,
+}
+
+impl DelimitedFields {
+    const ESCAPE: char = '\\';
+
+    /// ## Panics
+    ///
+    /// If `delimiter` is a backslash, as that's the escape character
+    pub fn new(delimiter: char) -> Self {
+        assert_ne!(
+            delimiter,
+            Self::ESCAPE,
+            "a backslash can't be used as the delimiter"
+        );
+        DelimitedFields { delimiter }
+    }
+
+    pub fn delimiter(&self) -> char {
+        self.delimiter
+    }
+
+    /// Escape any delimiter or backslash in `field`
+    pub fn escape(&self, field: &str) -> String {
+        let mut escaped = String::with_capacity(field.len());
+        for c in field.chars() {
+            if c == self.delimiter || c == Self::ESCAPE {
+                escaped.push(Self::ESCAPE);
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// Escape each of `fields` and join them with the delimiter
+    pub fn join<I, S>(&self, fields: I) -> String
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut joined = String::new();
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                joined.push(self.delimiter);
+            }
+            joined.push_str(&self.escape(field.as_ref()));
+        }
+        joined
+    }
+
+    /// Split `input` on every unescaped delimiter, unescaping each field
+    ///
+    /// An empty `input` is a single empty field.  A trailing backslash, which
+    /// [`DelimitedFields::join`] never produces, is kept as-is.
+    pub fn split(&self, input: &str) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut chars = input.chars();
+        while let Some(c) = chars.next() {
+            if c == Self::ESCAPE {
+                field.push(chars.next().unwrap_or(Self::ESCAPE));
+            } else if c == self.delimiter {
+                fields.push(std::mem::take(&mut field));
+            } else {
+                field.push(c);
+            }
+        }
+        fields.push(field);
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DelimitedFields;
+
+    #[test]
+    fn round_trip_with_delimiters() {
+        let fields = DelimitedFields::new(',');
+        let values = vec!["a,b", ",", "", "c\\,d", "trailing\\"];
+
+        let joined = fields.join(&values);
+        assert_eq!(joined, r"a\,b,\,,,c\\\,d,trailing\\");
+        assert_eq!(fields.split(&joined), values);
+    }
+
+    #[test]
+    fn other_delimiter() {
+        let fields = DelimitedFields::new('|');
+        assert_eq!(fields.join(&["1,2", "a|b"]), r"1,2|a\|b");
+        assert_eq!(fields.split(r"1,2|a\|b"), vec!["1,2", "a|b"]);
+    }
+
+    #[test]
+    fn split_unescaped() {
+        let fields = DelimitedFields::new(',');
+        assert_eq!(fields.split("1.0,2.0,3"), vec!["1.0", "2.0", "3"]);
+        assert_eq!(fields.split(""), vec![""]);
+        assert_eq!(fields.split(r"dangling\"), vec![r"dangling\"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "a backslash can't be used as the delimiter")]
+    fn backslash_delimiter() {
+        DelimitedFields::new('\\');
+    }
+}
@@ -27,6 +27,9 @@ extern crate bitflags;
 // expose our various derive macros
 pub use pgx_macros::*;
 
+#[cfg(feature = "chrono")]
+pub use chrono;
+
 pub mod callbacks;
 pub mod datum;
 pub mod enum_helper;
@@ -54,6 +57,7 @@ pub mod stringinfo;
 pub mod trigger_support;
 pub mod tupdesc;
 pub mod varlena;
+pub mod window;
 pub mod wrappers;
 pub mod xid;
 
@@ -85,6 +89,7 @@ pub use stringinfo::*;
 pub use trigger_support::*;
 pub use tupdesc::*;
 pub use varlena::*;
+pub use window::*;
 pub use wrappers::*;
 pub use xid::*;
 
@@ -210,15 +215,27 @@ pub static DEFAULT_TYPEID_SQL_MAPPING: Lazy<HashSet<RustSqlMapping>> = Lazy::new
     map_type!(m, TimeWithTimeZone, "time with time zone");
     map_type!(m, Timestamp, "timestamp");
     map_type!(m, TimestampWithTimeZone, "timestamp with time zone");
+    map_type!(m, datum::datetime::CivilDate, "date");
+    map_type!(m, datum::datetime::CivilTime, "time");
+    map_type!(m, datum::datetime::CivilTimestamp, "timestamp");
+    map_type!(m, datum::datetime::Interval, "interval");
     map_type!(m, pgx_pg_sys::PlannerInfo, "internal");
     map_type!(m, datum::Internal, "internal");
     map_type!(m, pgbox::PgBox<pgx_pg_sys::IndexAmRoutine>, "internal");
     map_type!(m, rel::PgRelation, "regclass");
+    map_type!(m, htup::PgHeapTuple, "record");
     map_type!(m, datum::Numeric, "numeric");
     map_type!(m, datum::AnyElement, "anyelement");
     map_type!(m, datum::AnyArray, "anyarray");
     map_type!(m, datum::Inet, "inet");
     map_type!(m, datum::Uuid, "uuid");
+    map_type!(m, datum::Varchar, "varchar");
+
+    #[cfg(feature = "chrono")]
+    {
+        map_type!(m, chrono::NaiveDateTime, "timestamp");
+        map_type!(m, chrono::DateTime<chrono::Utc>, "timestamp with time zone");
+    }
 
     m
 });
@@ -341,13 +358,45 @@ macro_rules! pg_sql_graph_magic {
                 env!("CARGO_CRATE_NAME"),
                 ".control"
             ));
-            let control_file = pgx::datum::sql_entity_graph::ControlFile::try_from(context)
+            let mut control_file = pgx::datum::sql_entity_graph::ControlFile::try_from(context)
                 .wrap_err_with(|| "Could not parse control file, is it valid?")?;
+            control_file.name = Some(String::from(env!("CARGO_CRATE_NAME")));
             Ok(control_file)
         }
     };
 }
 
+/// The installed version of the extension calling this macro, from the `pg_extension` catalog, or
+/// `None` if it isn't installed in the current database.
+///
+/// The version is the one in effect after any `ALTER EXTENSION ... UPDATE`, which isn't
+/// necessarily the `default_version` of the extension's `.control` file.
+///
+/// ```rust,ignore
+/// #[pg_extern]
+/// fn my_extension_version() -> String {
+///     extension_version!().expect("extension isn't installed")
+/// }
+/// ```
+#[macro_export]
+macro_rules! extension_version {
+    () => {
+        $crate::get_extension_version(env!("CARGO_CRATE_NAME"))
+    };
+}
+
+/// The installed version of the extension named `extname`, from the `pg_extension` catalog, or
+/// `None` if it isn't installed in the current database.
+///
+/// Generally [`extension_version!()`](extension_version) is preferred, for the calling extension's
+/// own version.
+pub fn get_extension_version(extname: &str) -> Option<String> {
+    Spi::get_one_with_args(
+        "SELECT extversion::text FROM pg_catalog.pg_extension WHERE extname = $1",
+        vec![(PgBuiltInOids::TEXTOID.oid(), extname.into_datum())],
+    )
+}
+
 /// Create the default SQL generator code.
 ///
 /// Accepts a single argument, which should be the crate name.
@@ -362,6 +411,10 @@ macro_rules! pg_sql_graph_magic {
 ///  * Supports [`EnvFilter`](pgx_utils::sql_entity_graph::reexports::tracing_subscriber::EnvFilter) log level configuration.
 ///  * Accepts `--sql path` and `--dot path` args, as well as a list of symbols.
 ///    These symbols are the `__pgx_internals` prefixed ones which `cargo pgx schema` detects.
+///  * Accepts `--if-not-exists`, which sets [`PgxSql::if_not_exists`](crate::datum::sql_entity_graph::PgxSql::if_not_exists).
+///  * Accepts `--pretty`, which writes [`PgxSql::to_sql_pretty`](crate::datum::sql_entity_graph::PgxSql::to_sql_pretty) instead.
+///  * Accepts `--manifest path`, to write [`PgxSql::function_manifest`](crate::datum::sql_entity_graph::PgxSql::function_manifest) to,
+///    and `--previous-manifest path`, to read [`PgxSql::previous_functions`](crate::datum::sql_entity_graph::PgxSql::previous_functions) from.
 ///
 /// Using different SQL generator code should be considered an advanced use case, and not
 /// recommended.
@@ -395,6 +448,10 @@ macro_rules! pg_binary_magic {
             let matches = clap::App::new("sql-generator")
                 .arg(clap::Arg::with_name("sql").long("sql").value_name("FILE").takes_value(true))
                 .arg(clap::Arg::with_name("dot").long("dot").value_name("FILE").takes_value(true))
+                .arg(clap::Arg::with_name("if-not-exists").long("if-not-exists"))
+                .arg(clap::Arg::with_name("pretty").long("pretty"))
+                .arg(clap::Arg::with_name("manifest").long("manifest").value_name("FILE").takes_value(true))
+                .arg(clap::Arg::with_name("previous-manifest").long("previous-manifest").value_name("FILE").takes_value(true))
                 // The `cargo-pgx` tool passes via env.
                 .arg(clap::Arg::with_name("symbols").value_name("SYMBOL").env("PGX_SQL_ENTITY_SYMBOLS").use_delimiter(true).multiple(true).takes_value(true))
                 .get_matches();
@@ -443,14 +500,27 @@ macro_rules! pg_binary_magic {
                 }
             };
 
-            let pgx_sql = PgxSql::build(pgx::DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(), pgx::DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(), entities.into_iter()).unwrap();
+            let mut pgx_sql = PgxSql::build(pgx::DEFAULT_TYPEID_SQL_MAPPING.clone().into_iter(), pgx::DEFAULT_SOURCE_ONLY_SQL_MAPPING.clone().into_iter(), entities.into_iter()).unwrap();
+            pgx_sql.if_not_exists = matches.is_present("if-not-exists");
+            if let Some(previous_manifest) = matches.value_of("previous-manifest") {
+                tracing::info!(previous_manifest = %previous_manifest, "Reading previous function manifest");
+                pgx_sql.previous_functions_from_file(previous_manifest)?;
+            }
 
             tracing::info!(path = %path, "Writing SQL");
-            pgx_sql.to_file(path)?;
+            if matches.is_present("pretty") {
+                pgx_sql.to_file_pretty(path)?;
+            } else {
+                pgx_sql.to_file(path)?;
+            }
             if let Some(dot_path) = dot {
                 tracing::info!(dot = %dot_path, "Writing Graphviz DOT");
                 pgx_sql.to_dot(dot_path)?;
             }
+            if let Some(manifest) = matches.value_of("manifest") {
+                tracing::info!(manifest = %manifest, "Writing function manifest");
+                pgx_sql.function_manifest_to_file(manifest)?;
+            }
             Ok(())
         }
     };
@@ -525,6 +525,215 @@ pub fn ereport(
     }
 }
 
+/// Something that names a SQLSTATE, either a [`PgSqlErrorCode`] or its five character code,
+/// such as `"22012"`
+pub trait ToSqlState {
+    fn to_sqlstate(&self) -> i32;
+}
+
+impl ToSqlState for PgSqlErrorCode {
+    fn to_sqlstate(&self) -> i32 {
+        *self as i32
+    }
+}
+
+impl ToSqlState for &str {
+    /// ## Panics
+    ///
+    /// If this isn't five digits or upper-case ASCII letters
+    fn to_sqlstate(&self) -> i32 {
+        let chars = self.chars().collect::<Vec<_>>();
+        match chars.as_slice() {
+            [ch1, ch2, ch3, ch4, ch5]
+                if chars
+                    .iter()
+                    .all(|ch| ch.is_ascii_digit() || ch.is_ascii_uppercase()) =>
+            {
+                MAKE_SQLSTATE(*ch1, *ch2, *ch3, *ch4, *ch5)
+            }
+            _ => panic!("`{}` is not a valid SQLSTATE", self),
+        }
+    }
+}
+
+/// A Postgres `ereport` message with its optional fields, as built by the [`ereport!`] macro.
+pub struct PgErrorReport {
+    level: PgLogLevel,
+    sqlerrcode: Option<i32>,
+    message: String,
+    detail: Option<String>,
+    hint: Option<String>,
+    file: &'static str,
+    lineno: u32,
+    colno: u32,
+}
+
+impl PgErrorReport {
+    pub fn new(level: PgLogLevel, file: &'static str, lineno: u32, colno: u32) -> Self {
+        PgErrorReport {
+            level,
+            sqlerrcode: None,
+            message: String::new(),
+            detail: None,
+            hint: None,
+            file,
+            lineno,
+            colno,
+        }
+    }
+
+    /// The SQLSTATE to report.  Like Postgres, this defaults to `ERRCODE_INTERNAL_ERROR` for
+    /// `ERROR` and above, `ERRCODE_WARNING` for `WARNING`, and `ERRCODE_SUCCESSFUL_COMPLETION`
+    /// otherwise
+    pub fn errcode(mut self, code: impl ToSqlState) -> Self {
+        self.sqlerrcode = Some(code.to_sqlstate());
+        self
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Emit this report.
+    ///
+    /// Reports of level `pg_sys::ERROR` and above will cause the current transaction to abort
+    pub fn report(self) {
+        use std::ffi::CString;
+        use std::os::raw::c_char;
+
+        extern "C" {
+            fn pgx_ereport_detail(
+                level: i32,
+                code: i32,
+                message: *const c_char,
+                detail: *const c_char,
+                hint: *const c_char,
+                file: *const c_char,
+                lineno: i32,
+                colno: i32,
+            );
+        }
+
+        fn to_cstring(value: String, what: &str) -> CString {
+            CString::new(value).unwrap_or_else(|_| {
+                CString::new(format!("{} contained a null byte", what))
+                    .expect("hardcoded error message failed")
+            })
+        }
+
+        let sqlerrcode = self.sqlerrcode.unwrap_or_else(|| match self.level as i32 {
+            level if level >= PgLogLevel::ERROR as i32 => {
+                PgSqlErrorCode::ERRCODE_INTERNAL_ERROR as i32
+            }
+            level if level == PgLogLevel::WARNING as i32 => PgSqlErrorCode::ERRCODE_WARNING as i32,
+            _ => PgSqlErrorCode::ERRCODE_SUCCESSFUL_COMPLETION as i32,
+        });
+        let message = to_cstring(self.message, "error message");
+        let detail = self.detail.map(|detail| to_cstring(detail, "error detail"));
+        let hint = self.hint.map(|hint| to_cstring(hint, "error hint"));
+        let file = to_cstring(self.file.to_string(), "filename");
+        let (level, lineno, colno) = (self.level, self.lineno, self.colno);
+
+        unsafe {
+            crate::guard(|| {
+                pgx_ereport_detail(
+                    level as i32,
+                    sqlerrcode,
+                    message.as_ptr(),
+                    detail
+                        .as_ref()
+                        .map_or(std::ptr::null(), |detail| detail.as_ptr()),
+                    hint.as_ref().map_or(std::ptr::null(), |hint| hint.as_ptr()),
+                    file.as_ptr(),
+                    lineno as i32,
+                    colno as i32,
+                );
+            });
+        }
+    }
+}
+
+/// Emit a Postgres `ereport` message with a SQLSTATE and optional detail and hint.
+///
+/// The first argument is a [`PgLogLevel`] and the rest are `field = value` pairs for the
+/// methods on [`PgErrorReport`]:  `errcode` (a [`PgSqlErrorCode`] or a SQLSTATE string),
+/// `message`, `detail`, and `hint`.  At `ERROR` level and above this never returns.
+///
+/// ```rust,no_run
+/// use pgx::*;
+///
+/// #[pg_extern]
+/// fn checked_divide(a: i32, b: i32) -> i32 {
+///     if b == 0 {
+///         ereport!(
+///             ERROR,
+///             errcode = "22012",
+///             message = "division by zero",
+///             detail = format!("tried to divide {} by zero", a),
+///             hint = "check the divisor first",
+///         );
+///     }
+///     a / b
+/// }
+/// ```
+#[macro_export]
+macro_rules! ereport {
+    (ERROR, $($field:ident = $value:expr),+ $(,)?) => ({
+        $crate::ereport!(@report ERROR, $($field = $value),+);
+        unreachable!("ereport(ERROR) returned")
+    });
+    (FATAL, $($field:ident = $value:expr),+ $(,)?) => ({
+        $crate::ereport!(@report FATAL, $($field = $value),+);
+        unreachable!("ereport(FATAL) returned")
+    });
+    (PANIC, $($field:ident = $value:expr),+ $(,)?) => ({
+        $crate::ereport!(@report PANIC, $($field = $value),+);
+        unreachable!("ereport(PANIC) returned")
+    });
+    ($level:ident, $($field:ident = $value:expr),+ $(,)?) => (
+        $crate::ereport!(@report $level, $($field = $value),+)
+    );
+    (@report $level:ident, $($field:ident = $value:expr),+) => (
+        $crate::log::PgErrorReport::new($crate::log::PgLogLevel::$level, file!(), line!(), column!())
+            $(.$field($value))+
+            .report()
+    );
+}
+
+/// Log a message at the given [`PgLogLevel`].  At `ERROR` level and above this never returns.
+///
+/// This macro accepts arguments like the [`println`](std::println) and [`format`](std::format)
+/// macros, after the level:  `elog!(WARNING, "{} rows skipped", skipped)`.
+#[macro_export]
+macro_rules! elog {
+    (ERROR, $($arg:tt)*) => ({
+        $crate::log::elog($crate::log::PgLogLevel::ERROR, format!($($arg)*).as_str());
+        unreachable!("elog(ERROR) returned")
+    });
+    (FATAL, $($arg:tt)*) => ({
+        $crate::log::elog($crate::log::PgLogLevel::FATAL, format!($($arg)*).as_str());
+        unreachable!("elog(FATAL) returned")
+    });
+    (PANIC, $($arg:tt)*) => ({
+        $crate::log::elog($crate::log::PgLogLevel::PANIC, format!($($arg)*).as_str());
+        unreachable!("elog(PANIC) returned")
+    });
+    ($level:ident, $($arg:tt)*) => (
+        $crate::log::elog($crate::log::PgLogLevel::$level, format!($($arg)*).as_str())
+    );
+}
+
 /// Log to Postgres' `debug5` log level.
 ///
 /// This macro accepts arguments like the [`println`](std::println) and [`format`](std::format) macros.
@@ -902,3 +1111,32 @@ macro_rules! check_for_interrupts {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{PgSqlErrorCode, ToSqlState};
+
+    #[test]
+    fn sqlstate_from_str() {
+        assert_eq!(
+            PgSqlErrorCode::ERRCODE_DIVISION_BY_ZERO as i32,
+            "22012".to_sqlstate()
+        );
+        assert_eq!(
+            PgSqlErrorCode::ERRCODE_INTERNAL_ERROR as i32,
+            "XX000".to_sqlstate()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`2201` is not a valid SQLSTATE")]
+    fn sqlstate_from_short_str() {
+        "2201".to_sqlstate();
+    }
+
+    #[test]
+    #[should_panic(expected = "`xx000` is not a valid SQLSTATE")]
+    fn sqlstate_from_lowercase_str() {
+        "xx000".to_sqlstate();
+    }
+}
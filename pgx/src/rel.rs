@@ -127,15 +127,26 @@ impl PgRelation {
     /// Additionally, the relation is closed via `pg_sys::RelationClose()` when this instance is
     /// dropped.
     pub fn open_with_name_and_share_lock(relname: &str) -> std::result::Result<Self, &'static str> {
+        PgRelation::open_with_name_and_lock(relname, pg_sys::AccessShareLock as pg_sys::LOCKMODE)
+    }
+
+    /// Given a relation name, use `pg_sys::to_regclass` to look up its oid, and then
+    /// open it via [`PgRelation::with_lock`] with the specified kind of lock
+    ///
+    /// If the specified relation name is not found, we return an `Err(&str)`.
+    ///
+    /// The opened relation is closed, and the lock released, via `pg_sys::relation_close()` when
+    /// this instance is dropped.
+    pub fn open_with_name_and_lock(
+        relname: &str,
+        lockmode: pg_sys::LOCKMODE,
+    ) -> std::result::Result<Self, &'static str> {
         unsafe {
             match direct_function_call::<pg_sys::Oid>(
                 pg_sys::to_regclass,
                 vec![relname.into_datum()],
             ) {
-                Some(oid) => Ok(PgRelation::with_lock(
-                    oid,
-                    pg_sys::AccessShareLock as pg_sys::LOCKMODE,
-                )),
+                Some(oid) => Ok(PgRelation::with_lock(oid, lockmode)),
                 None => Err("no such relation"),
             }
         }
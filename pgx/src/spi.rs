@@ -3,9 +3,12 @@
 
 //! Safe access to Postgres' *Server Programming Interface* (SPI).
 
-use crate::{pg_sys, FromDatum, IntoDatum, Json, PgMemoryContexts, PgOid};
+use crate::{
+    pg_guard, pg_sys, FromDatum, IntoDatum, Json, JsonB, PgBuiltInOids, PgMemoryContexts, PgOid,
+};
 use enum_primitive_derive::*;
 use num_traits::FromPrimitive;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::{Index, IndexMut};
@@ -52,10 +55,231 @@ pub enum SpiError {
     RelNotFound = 13,
 }
 
+/// Why a row from a SPI query couldn't be converted into a [`SpiRow`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpiRowError {
+    /// The query has no column of this name
+    MissingColumn { column: String },
+    /// The column isn't of the field's SQL type
+    TypeMismatch {
+        column: String,
+        expected: String,
+        actual: String,
+    },
+    /// The column is NULL but its field isn't an `Option`
+    UnexpectedNull { column: String },
+}
+
+impl std::fmt::Display for SpiRowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpiRowError::MissingColumn { column } => {
+                write!(f, "the query has no column named `{}`", column)
+            }
+            SpiRowError::TypeMismatch {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column `{}` is of type `{}`, but `{}` was expected",
+                column, actual, expected
+            ),
+            SpiRowError::UnexpectedNull { column } => {
+                write!(
+                    f,
+                    "column `{}` is NULL, but its field isn't an `Option`",
+                    column
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpiRowError {}
+
+/// A type which can be built from a row returned by a SPI query, usually via `#[derive(SpiRow)]`,
+/// which matches each of a struct's fields to the column of the same name.
+///
+/// ```rust,no_run
+/// use pgx::*;
+///
+/// #[derive(SpiRow)]
+/// struct Dog {
+///     name: String,
+///     age: Option<i32>,
+/// }
+///
+/// Spi::execute(|client| {
+///     let dogs = client
+///         .select("SELECT name, age FROM dogs", None, None)
+///         .into_typed::<Dog>()
+///         .expect("failed to read dogs");
+/// });
+/// ```
+pub trait SpiRow: Sized {
+    fn from_spi_row(row: &SpiHeapTupleData) -> std::result::Result<Self, SpiRowError>;
+}
+
 pub struct Spi;
 
 pub struct SpiClient;
 
+/// The longest piece of query text, in bytes, that [`SpiQueryErrorContext`] will report
+const SPI_QUERY_CONTEXT_MAX_LEN: usize = 1024;
+
+/// The most plans [`Spi::run_fast`] keeps cached.  Caching another frees the least recently used.
+const MAX_CACHED_PLANS: usize = 128;
+
+thread_local! {
+    /// The plans of [`Spi::run_fast`].  Postgres backends are single-threaded, so this is
+    /// effectively per-backend
+    static CACHED_PLANS: RefCell<CachedPlans> = RefCell::new(CachedPlans::default());
+}
+
+/// Plans kept with `SPI_keepplan()`, by query, along with when each was last used
+#[derive(Default)]
+struct CachedPlans {
+    plans: HashMap<String, (pg_sys::SPIPlanPtr, u64)>,
+    uses: u64,
+}
+
+impl CachedPlans {
+    fn get(&mut self, query: &str) -> Option<pg_sys::SPIPlanPtr> {
+        self.uses += 1;
+        let uses = self.uses;
+        self.plans.get_mut(query).map(|(plan, last_used)| {
+            *last_used = uses;
+            *plan
+        })
+    }
+
+    fn insert(&mut self, query: &str, plan: pg_sys::SPIPlanPtr) {
+        if self.plans.len() >= MAX_CACHED_PLANS {
+            let least_recently_used = self
+                .plans
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(query, _)| query.clone());
+            if let Some((plan, _)) = least_recently_used.and_then(|query| self.plans.remove(&query))
+            {
+                unsafe {
+                    pg_sys::SPI_freeplan(plan);
+                }
+            }
+        }
+        self.plans.insert(query.to_string(), (plan, self.uses));
+    }
+
+    fn clear(&mut self) -> usize {
+        let count = self.plans.len();
+        for (_, (plan, _)) in self.plans.drain() {
+            unsafe {
+                pg_sys::SPI_freeplan(plan);
+            }
+        }
+        count
+    }
+}
+
+/// a struct to manage our SPI connection lifetime
+struct SpiConnection;
+
+impl SpiConnection {
+    /// Connect to Postgres' SPI system.  A `nonatomic` connection allows [`Spi::commit()`] and
+    /// [`Spi::rollback()`], if the `CALL` it's made for does
+    fn connect(nonatomic: bool) -> Self {
+        #[cfg(feature = "pg10")]
+        let status_code = {
+            assert!(
+                !nonatomic,
+                "nonatomic SPI connections need Postgres 11 or later"
+            );
+            unsafe { pg_sys::SPI_connect() }
+        };
+        #[cfg(not(feature = "pg10"))]
+        let status_code = unsafe {
+            pg_sys::SPI_connect_ext(if nonatomic {
+                pg_sys::SPI_OPT_NONATOMIC as i32
+            } else {
+                0
+            })
+        };
+        Spi::check_status(status_code);
+        SpiConnection
+    }
+}
+
+impl Drop for SpiConnection {
+    /// when SpiConnection is dropped, we make sure to disconnect from SPI
+    fn drop(&mut self) {
+        // disconnect from SPI.  This also happens while unwinding from a panic or
+        // elog(ERROR), so that a caught panic doesn't leave the connection open, and in
+        // that case we can't panic again over a bad status code
+        let status_code = unsafe { pg_sys::SPI_finish() };
+        if !std::thread::panicking() {
+            Spi::check_status(status_code);
+        }
+    }
+}
+
+/// While alive, adds the text of the query being executed as a `CONTEXT` line to any Postgres
+/// ERROR raised, so it's clear which statement failed
+struct SpiQueryErrorContext {
+    callback: pg_sys::ErrorContextCallback,
+    query: std::ffi::CString,
+}
+
+impl SpiQueryErrorContext {
+    fn push(query: &str) -> Box<Self> {
+        let query = std::ffi::CString::new(SpiQueryErrorContext::truncate(query).as_ref())
+            .expect("query contained a null byte");
+        let mut context = Box::new(SpiQueryErrorContext {
+            callback: pg_sys::ErrorContextCallback {
+                previous: unsafe { pg_sys::error_context_stack },
+                callback: Some(spi_query_error_context_callback),
+                arg: std::ptr::null_mut(),
+            },
+            query,
+        });
+
+        // the Box keeps these addresses stable until we're dropped
+        context.callback.arg = context.query.as_ptr() as *mut std::os::raw::c_void;
+        unsafe {
+            pg_sys::error_context_stack = &mut context.callback;
+        }
+        context
+    }
+
+    fn truncate(query: &str) -> std::borrow::Cow<'_, str> {
+        if query.len() <= SPI_QUERY_CONTEXT_MAX_LEN {
+            return query.into();
+        }
+
+        let mut end = SPI_QUERY_CONTEXT_MAX_LEN;
+        while !query.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &query[..end]).into()
+    }
+}
+
+impl Drop for SpiQueryErrorContext {
+    fn drop(&mut self) {
+        unsafe {
+            pg_sys::error_context_stack = self.callback.previous;
+        }
+    }
+}
+
+#[pg_guard]
+unsafe extern "C" fn spi_query_error_context_callback(arg: *mut std::os::raw::c_void) {
+    pg_sys::errcontext_msg(
+        "Spi query: \"%s\"\0".as_ptr() as *const std::os::raw::c_char,
+        arg as *const std::os::raw::c_char,
+    );
+}
+
 #[derive(Debug)]
 pub struct SpiTupleTable {
     status_code: SpiOk,
@@ -154,6 +378,76 @@ impl Spi {
         .unwrap()
     }
 
+    /// the first column of the first row returned by `query`, whatever its SQL type, as JSON.
+    ///
+    /// The value is converted by Postgres' `to_jsonb()`, so numbers, booleans, arrays and
+    /// composites become their JSON equivalents, and values of other types become a string of
+    /// their type's text output.  `None` if the query returns no rows, or the value is NULL.
+    pub fn get_one_json(query: &str) -> Option<serde_json::Value> {
+        Spi::connect(|client| {
+            let (datum, type_oid) =
+                match client.select(query, Some(1), None).first().get_heap_tuple() {
+                    Some(row) => {
+                        let entry = row.by_ordinal(1)?;
+                        match entry.type_oid {
+                            // an untyped literal, like the 'x' of `SELECT 'x'`, is a cstring that
+                            // to_jsonb() can't take, so it's bound as the text it would become
+                            pg_sys::UNKNOWNOID => (
+                                entry.datum.map(|datum| unsafe {
+                                    pg_sys::cstring_to_text(datum as *const std::os::raw::c_char)
+                                        as pg_sys::Datum
+                                }),
+                                pg_sys::TEXTOID,
+                            ),
+                            type_oid => (entry.datum, type_oid),
+                        }
+                    }
+                    None => return Ok(None),
+                };
+
+            Ok(client
+                .select(
+                    "SELECT to_jsonb($1)",
+                    Some(1),
+                    Some(vec![(PgOid::from(type_oid), datum)]),
+                )
+                .first()
+                .get_one::<JsonB>())
+        })
+        .map(|jsonb| jsonb.0)
+    }
+
+    /// collect the first column of every row returned by `query`.  NULLs are returned as `None`
+    ///
+    /// This reads the entire result set into memory, so it's meant for queries that return a
+    /// modest number of rows.
+    pub fn get_column<A: FromDatum + IntoDatum>(query: &str) -> Vec<Option<A>> {
+        let outer_memory_context = PgMemoryContexts::CurrentMemoryContext.value();
+        let mut column = Vec::new();
+
+        Spi::connect(|client| {
+            for row in client.select(query, None, None) {
+                let entry = row.by_ordinal(1)?;
+
+                // copy each value out to the caller's memory context before SPI is finished
+                column.push(match entry.datum {
+                    Some(datum) => unsafe {
+                        A::from_datum_in_memory_context(
+                            PgMemoryContexts::For(outer_memory_context),
+                            datum,
+                            false,
+                            entry.type_oid,
+                        )
+                    },
+                    None => None,
+                });
+            }
+            Ok(Some(()))
+        });
+
+        column
+    }
+
     /// just run an arbitrary SQL statement.
     ///
     /// ## Safety
@@ -165,6 +459,96 @@ impl Spi {
         })
     }
 
+    /// run an arbitrary SQL statement with bound `$1..$n` arguments, returning the number of rows
+    /// it processed, or the error SPI rejected it with, such as [`SpiError::Transaction`] for a
+    /// `COMMIT`.  A Postgres ERROR raised by the statement itself is raised as usual.
+    ///
+    /// ## Safety
+    ///
+    /// The statement runs in read/write mode
+    pub fn run_with_args(
+        query: &str,
+        args: Vec<(PgOid, Option<pg_sys::Datum>)>,
+    ) -> std::result::Result<u64, SpiError> {
+        let mut processed = Ok(0);
+        Spi::connect(|_client| {
+            let status_code = SpiClient::execute_status(query, false, None, Some(args));
+            processed = match SpiError::from_i32(-status_code) {
+                Some(e) if status_code < 0 => Err(e),
+                _ => Ok(unsafe { pg_sys::SPI_processed }),
+            };
+            Ok(Some(()))
+        });
+        processed
+    }
+
+    /// bulk-insert `rows` into the named `columns` of `table`, returning the number of rows
+    /// inserted.
+    ///
+    /// Each row holds one datum per column, in the same order as `columns`, with `None` for a
+    /// NULL.  The datums must be of each column's type, which is looked up from the system
+    /// catalog.  `table` may be schema-qualified and is resolved against the current
+    /// `search_path`.
+    ///
+    /// This prepares a single `INSERT` statement and executes its plan once per row.  Skipping the
+    /// parse and plan steps for each row is what makes it much faster than issuing individual
+    /// `INSERT`s, though it isn't `COPY`, which SPI refuses to run `FROM STDIN`.
+    ///
+    /// ## Panics
+    ///
+    /// If `table` doesn't exist, one of `columns` isn't in it, or a row doesn't have a datum for
+    /// every column
+    pub fn insert_many<I: IntoIterator<Item = Vec<Option<pg_sys::Datum>>>>(
+        table: &str,
+        columns: &[&str],
+        rows: I,
+    ) -> u64 {
+        let mut inserted = 0;
+        Spi::connect(|mut client| {
+            inserted = client.insert_many(table, columns, rows);
+            Ok(Some(()))
+        });
+        inserted
+    }
+
+    /// run an arbitrary SQL statement in read-only mode.
+    ///
+    /// This is what `STABLE` and `IMMUTABLE` functions should use:  Postgres rejects any statement
+    /// that would modify the database, and the statement sees the snapshot the calling query
+    /// started with, rather than one taken for the statement itself.
+    pub fn run_read_only(query: &str) {
+        Spi::execute(|client| {
+            client.select_read_only(query, None, None);
+        })
+    }
+
+    /// run an arbitrary SQL statement, reusing a plan for it that's cached for the life of
+    /// the backend.
+    ///
+    /// This is meant for in-database microbenchmarks, such as a `#[pg_test]` that executes the
+    /// same statement many times.  The first call parses and plans `query` and keeps the plan
+    /// via `SPI_keepplan()`.  Later calls with an identical query string skip straight to
+    /// execution.
+    ///
+    /// ## Trade-offs
+    ///
+    /// - Up to 128 plans are cached.  Caching another frees the least recently used, and
+    ///   [`Spi::clear_cached_plans`] frees them all
+    /// - The statement runs in read/write mode
+    ///
+    /// There's no statement logging to turn off:  `log_statement` only applies to statements sent
+    /// by the client, never to those run through SPI.
+    pub fn run_fast(query: &str) {
+        Spi::execute(|mut client| {
+            client.update_cached(query, None);
+        })
+    }
+
+    /// free every plan that [`Spi::run_fast`] has cached, returning how many there were
+    pub fn clear_cached_plans() -> usize {
+        CACHED_PLANS.with(|plans| plans.borrow_mut().clear())
+    }
+
     /// explain a query, returning its result in json form
     pub fn explain(query: &str) -> Json {
         Spi::connect(|mut client| {
@@ -188,6 +572,62 @@ impl Spi {
         });
     }
 
+    /// run the provided closure inside a Postgres subtransaction
+    ///
+    /// The subtransaction is committed if the closure returns `Ok`, and rolled back if it returns
+    /// `Err` or panics.  A panic (including a Postgres ERROR) is rethrown once the subtransaction
+    /// has been rolled back, so the outer transaction is left exactly as it was before the call.
+    pub fn with_subtransaction<R, E, F>(f: F) -> Result<R, E>
+    where
+        F: FnOnce() -> Result<R, E> + std::panic::UnwindSafe,
+    {
+        unsafe {
+            let old_context = pg_sys::CurrentMemoryContext;
+            let old_owner = pg_sys::CurrentResourceOwner;
+
+            pg_sys::BeginInternalSubTransaction(std::ptr::null_mut());
+
+            // the closure runs in the subtransaction's memory context, just like it would
+            // inside a plpgsql `BEGIN ... EXCEPTION` block
+            let result = std::panic::catch_unwind(f);
+
+            match result {
+                Ok(Ok(value)) => {
+                    pg_sys::ReleaseCurrentSubTransaction();
+                    pg_sys::CurrentMemoryContext = old_context;
+                    pg_sys::CurrentResourceOwner = old_owner;
+                    Ok(value)
+                }
+                Ok(Err(e)) => {
+                    pg_sys::RollbackAndReleaseCurrentSubTransaction();
+                    pg_sys::CurrentMemoryContext = old_context;
+                    pg_sys::CurrentResourceOwner = old_owner;
+                    Err(e)
+                }
+                Err(panic) => {
+                    pg_sys::CurrentMemoryContext = old_context;
+
+                    if panic.downcast_ref::<pg_sys::JumpContext>().is_some() {
+                        // a Postgres ERROR -- copy it out of the ErrorContext so it survives the
+                        // subtransaction abort, and then rethrow it in the outer transaction
+                        let edata = pg_sys::CopyErrorData();
+                        pg_sys::FlushErrorState();
+                        pg_sys::RollbackAndReleaseCurrentSubTransaction();
+                        pg_sys::CurrentMemoryContext = old_context;
+                        pg_sys::CurrentResourceOwner = old_owner;
+                        pg_sys::ReThrowError(edata);
+                        unreachable!("ReThrowError() failed to rethrow");
+                    }
+
+                    pg_sys::RollbackAndReleaseCurrentSubTransaction();
+                    pg_sys::CurrentMemoryContext = old_context;
+                    pg_sys::CurrentResourceOwner = old_owner;
+                    std::panic::resume_unwind(panic)
+                }
+            }
+        }
+    }
+
     /// execute SPI commands via the provided `SpiClient` and return a value from SPI which is
     /// automatically copied into the `CurrentMemoryContext` at the time of this function call
     pub fn connect<
@@ -199,32 +639,12 @@ impl Spi {
         let outer_memory_context =
             PgMemoryContexts::For(PgMemoryContexts::CurrentMemoryContext.value());
 
-        /// a struct to manage our SPI connection lifetime
-        struct SpiConnection;
-        impl SpiConnection {
-            /// Connect to Postgres' SPI system
-            fn connect() -> Self {
-                // connect to SPI
-                Spi::check_status(unsafe { pg_sys::SPI_connect() });
-                SpiConnection
-            }
-        }
-
-        impl Drop for SpiConnection {
-            /// when SpiConnection is dropped, we make sure to disconnect from SPI
-            fn drop(&mut self) {
-                // disconnect from SPI
-                Spi::check_status(unsafe { pg_sys::SPI_finish() });
-            }
-        }
-
         // connect to SPI
-        let _connection = SpiConnection::connect();
+        let _connection = SpiConnection::connect(false);
 
         // run the provided closure within the memory context that SPI_connect()
-        // just put us un.  We'll disconnect from SPI when the closure is finished.
-        // If there's a panic or elog(ERROR), we don't care about also disconnecting from
-        // SPI b/c Postgres will do that for us automatically
+        // just put us un.  We'll disconnect from SPI when the closure is finished, or when
+        // `_connection` is dropped while unwinding from a panic or elog(ERROR)
         match f(SpiClient) {
             // copy the result to the outer memory context we saved above
             Ok(result) => {
@@ -276,6 +696,54 @@ impl Spi {
     }
 }
 
+/// Transaction control, for `#[pg_proc]` procedures
+#[cfg(any(feature = "pg11", feature = "pg12", feature = "pg13", feature = "pg14"))]
+impl Spi {
+    /// Commit the current transaction, and start a new one
+    ///
+    /// This is only possible from a `#[pg_proc]` procedure run by a top-level `CALL`, and not
+    /// from within [`Spi::connect()`].  Otherwise, such as when the `CALL` is in an explicit
+    /// transaction block or a function, Postgres raises an "invalid transaction termination" ERROR.
+    ///
+    /// Anything allocated in a memory context of the committed transaction is freed.
+    pub fn commit() {
+        unsafe {
+            pg_sys::SPI_commit();
+            pg_sys::SPI_start_transaction();
+        }
+    }
+
+    /// Roll back the current transaction, and start a new one
+    ///
+    /// The same restrictions apply as for [`Spi::commit()`].
+    pub fn rollback() {
+        unsafe {
+            pg_sys::SPI_rollback();
+            pg_sys::SPI_start_transaction();
+        }
+    }
+
+    /// Run the `body` of a `#[pg_proc]` procedure within an SPI connection, which allows
+    /// [`Spi::commit()`] and [`Spi::rollback()`] when its `CALL` does.
+    ///
+    /// # Safety
+    ///
+    /// The provided `fcinfo` must be valid otherwise this function results in undefined behavior
+    #[doc(hidden)]
+    pub unsafe fn in_procedure<R, F: FnOnce() -> R>(
+        fcinfo: pg_sys::FunctionCallInfo,
+        body: F,
+    ) -> R {
+        let context = (*fcinfo).context;
+        let nonatomic = crate::is_a(context, pg_sys::NodeTag_T_CallContext)
+            && !(*(context as *mut pg_sys::CallContext)).atomic;
+
+        // disconnected when `_connection` is dropped, even while unwinding from a panic
+        let _connection = SpiConnection::connect(nonatomic);
+        body()
+    }
+}
+
 impl SpiClient {
     /// perform a SELECT statement
     pub fn select(
@@ -290,7 +758,7 @@ impl SpiClient {
         //    using SPI; that could result in very confusing behavior, since the read-only queries
         //    would not see the results of any database updates done by the read-write queries.
         //
-        // As such, we don't actually set read-only to true here
+        // As such, we don't actually set read-only to true here.  Use `select_read_only()` for that
 
         // TODO:  can we detect if the command counter (or something?) has incremented and if yes
         //        then we set read_only=false, else we can set it to true?
@@ -298,6 +766,20 @@ impl SpiClient {
         SpiClient::execute(query, false, limit, args)
     }
 
+    /// perform a SELECT statement in read-only mode
+    ///
+    /// `STABLE` and `IMMUTABLE` functions should use this instead of [`SpiClient::select`].
+    /// Postgres rejects any statement that would modify the database, and the statement runs with
+    /// the snapshot of the calling query, so it sees the same data the caller does.
+    pub fn select_read_only(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+        args: Option<Vec<(PgOid, Option<pg_sys::Datum>)>>,
+    ) -> SpiTupleTable {
+        SpiClient::execute(query, true, limit, args)
+    }
+
     /// perform any query (including utility statements) that modify the database in some way
     pub fn update(
         &mut self,
@@ -308,18 +790,179 @@ impl SpiClient {
         SpiClient::execute(query, false, limit, args)
     }
 
+    /// perform any query (including utility statements) using a plan that is prepared once and
+    /// then cached for the life of the backend.  See [`Spi::run_fast`] for the trade-offs.
+    pub fn update_cached(&mut self, query: &str, limit: Option<i64>) -> SpiTupleTable {
+        SpiClient::execute_cached(query, false, limit)
+    }
+
+    /// bulk-insert rows into a table.  See [`Spi::insert_many`]
+    pub fn insert_many<I: IntoIterator<Item = Vec<Option<pg_sys::Datum>>>>(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        rows: I,
+    ) -> u64 {
+        let relid = self.relation_oid(table);
+        let mut argtypes = columns
+            .iter()
+            .map(|column| self.column_type_oid(relid, table, column))
+            .collect::<Vec<_>>();
+
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quoted_relation_name(relid),
+            columns
+                .iter()
+                .map(|column| format!("\"{}\"", column.replace('"', r#""""#)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            (1..=columns.len())
+                .map(|n| format!("${}", n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let _context = SpiQueryErrorContext::push(&query);
+
+        let src = std::ffi::CString::new(query.as_str()).expect("query contained a null byte");
+        let plan = unsafe {
+            pg_sys::SPI_prepare(src.as_ptr(), columns.len() as i32, argtypes.as_mut_ptr())
+        };
+        if plan.is_null() {
+            SpiClient::check_status(&query, unsafe { pg_sys::SPI_result });
+        }
+
+        let mut inserted = 0;
+        let mut datums = Vec::with_capacity(columns.len());
+        let mut nulls = Vec::with_capacity(columns.len());
+        for row in rows {
+            if row.len() != columns.len() {
+                panic!(
+                    "row {} has {} value(s), but {} column(s) were named",
+                    inserted + 1,
+                    row.len(),
+                    columns.len()
+                );
+            }
+
+            datums.clear();
+            nulls.clear();
+            for datum in row {
+                datums.push(datum.unwrap_or(0));
+                nulls.push(if datum.is_some() { b' ' } else { b'n' } as std::os::raw::c_char);
+            }
+
+            let status_code = unsafe {
+                pg_sys::SPI_execute_plan(plan, datums.as_mut_ptr(), nulls.as_ptr(), false, 0)
+            };
+            SpiClient::check_status(&query, status_code);
+            inserted += 1;
+        }
+
+        unsafe {
+            pg_sys::SPI_freeplan(plan);
+        }
+        inserted
+    }
+
+    /// the oid of the relation named `table`, raising an ERROR if it doesn't exist
+    fn relation_oid(&self, table: &str) -> pg_sys::Oid {
+        self.select(
+            "SELECT $1::regclass::oid",
+            None,
+            Some(vec![(PgBuiltInOids::TEXTOID.oid(), table.into_datum())]),
+        )
+        .first()
+        .get_one::<pg_sys::Oid>()
+        .expect("regclass lookup returned NULL")
+    }
+
+    /// the type of `column` in the relation `relid`, named `table`, panicking if it doesn't exist
+    fn column_type_oid(&self, relid: pg_sys::Oid, table: &str, column: &str) -> pg_sys::Oid {
+        self.select(
+            "SELECT atttypid FROM pg_catalog.pg_attribute \
+             WHERE attrelid = $1 AND attname = $2 AND attnum > 0 AND NOT attisdropped",
+            None,
+            Some(vec![
+                (PgBuiltInOids::OIDOID.oid(), relid.into_datum()),
+                (PgBuiltInOids::TEXTOID.oid(), column.into_datum()),
+            ]),
+        )
+        .first()
+        .get_one::<pg_sys::Oid>()
+        .unwrap_or_else(|| {
+            panic!(
+                "column \"{}\" of relation \"{}\" does not exist",
+                column, table
+            )
+        })
+    }
+
+    fn execute_cached(query: &str, read_only: bool, limit: Option<i64>) -> SpiTupleTable {
+        let _context = SpiQueryErrorContext::push(query);
+
+        unsafe {
+            pg_sys::SPI_tuptable = std::ptr::null_mut();
+        }
+
+        let plan = match CACHED_PLANS.with(|plans| plans.borrow_mut().get(query)) {
+            Some(plan) => plan,
+            None => {
+                let src = std::ffi::CString::new(query).expect("query contained a null byte");
+                let plan = unsafe { pg_sys::SPI_prepare(src.as_ptr(), 0, std::ptr::null_mut()) };
+                if plan.is_null() {
+                    SpiClient::check_status(query, unsafe { pg_sys::SPI_result });
+                }
+
+                // move the plan out of the SPI procedure's memory context so it outlives
+                // this connection
+                let status_code = unsafe { pg_sys::SPI_keepplan(plan) };
+                if status_code != 0 {
+                    SpiClient::check_status(query, status_code);
+                }
+
+                CACHED_PLANS.with(|plans| plans.borrow_mut().insert(query, plan));
+                plan
+            }
+        };
+
+        let status_code = unsafe {
+            pg_sys::SPI_execute_plan(
+                plan,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                read_only,
+                limit.unwrap_or(0),
+            )
+        };
+
+        SpiClient::prepare_tuple_table(query, status_code)
+    }
+
     fn execute(
         query: &str,
         read_only: bool,
         limit: Option<i64>,
         args: Option<Vec<(PgOid, Option<pg_sys::Datum>)>>,
     ) -> SpiTupleTable {
+        let status_code = SpiClient::execute_status(query, read_only, limit, args);
+        SpiClient::prepare_tuple_table(query, status_code)
+    }
+
+    /// execute `query`, returning SPI's status code for it without checking it
+    fn execute_status(
+        query: &str,
+        read_only: bool,
+        limit: Option<i64>,
+        args: Option<Vec<(PgOid, Option<pg_sys::Datum>)>>,
+    ) -> i32 {
         unsafe {
             pg_sys::SPI_tuptable = std::ptr::null_mut();
         }
 
         let src = std::ffi::CString::new(query).expect("query contained a null byte");
-        let status_code = match args {
+        let _context = SpiQueryErrorContext::push(query);
+        match args {
             Some(args) => {
                 let nargs = args.len();
                 let mut argtypes = vec![];
@@ -355,10 +998,24 @@ impl SpiClient {
                 }
             }
             None => unsafe { pg_sys::SPI_execute(src.as_ptr(), read_only, limit.unwrap_or(0)) },
-        };
+        }
+    }
 
+    /// like [`Spi::check_status`], but names the query that failed
+    fn check_status(query: &str, status_code: i32) -> SpiOk {
+        match SpiError::from_i32(-status_code) {
+            Some(e) if status_code < 0 => panic!(
+                "{:?} while executing query: \"{}\"",
+                e,
+                SpiQueryErrorContext::truncate(query)
+            ),
+            _ => Spi::check_status(status_code),
+        }
+    }
+
+    fn prepare_tuple_table(query: &str, status_code: i32) -> SpiTupleTable {
         SpiTupleTable {
-            status_code: Spi::check_status(status_code),
+            status_code: SpiClient::check_status(query, status_code),
             table: unsafe { pg_sys::SPI_tuptable },
             size: unsafe { pg_sys::SPI_processed as usize },
             tupdesc: if unsafe { pg_sys::SPI_tuptable }.is_null() {
@@ -371,6 +1028,19 @@ impl SpiClient {
     }
 }
 
+/// the schema-qualified name of the relation `relid`, each part quoted if it needs to be
+fn quoted_relation_name(relid: pg_sys::Oid) -> String {
+    unsafe {
+        let name = pg_sys::quote_qualified_identifier(
+            pg_sys::get_namespace_name(pg_sys::get_rel_namespace(relid)),
+            pg_sys::get_rel_name(relid),
+        );
+        std::ffi::CStr::from_ptr(name)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
 impl SpiTupleTable {
     /// `SpiTupleTable`s are positioned before the start, for iteration purposes.
     ///
@@ -390,6 +1060,129 @@ impl SpiTupleTable {
         self.len() == 0
     }
 
+    /// How many columns are in each row?
+    ///
+    /// Statements that don't return rows, such as an `INSERT` without `RETURNING`, have zero columns
+    pub fn column_count(&self) -> usize {
+        match self.tupdesc {
+            Some(tupdesc) => unsafe { (*tupdesc).natts as usize },
+            None => 0,
+        }
+    }
+
+    /// The name of the column at the specified ordinal position.
+    ///
+    /// The ordinal position is 1-based.  If it is out of bounds, `None` is returned
+    pub fn column_name(&self, ordinal: i32) -> Option<String> {
+        if ordinal < 1 || ordinal as usize > self.column_count() {
+            return None;
+        }
+
+        unsafe {
+            let name = pg_sys::SPI_fname(self.tupdesc?, ordinal);
+            if name.is_null() {
+                return None;
+            }
+
+            let result = std::ffi::CStr::from_ptr(name)
+                .to_string_lossy()
+                .into_owned();
+            pg_sys::pfree(name as crate::void_mut_ptr);
+            Some(result)
+        }
+    }
+
+    /// The type of the column at the specified ordinal position.
+    ///
+    /// The ordinal position is 1-based.  If it is out of bounds, `None` is returned
+    pub fn column_type(&self, ordinal: i32) -> Option<PgOid> {
+        if ordinal < 1 || ordinal as usize > self.column_count() {
+            return None;
+        }
+
+        Some(PgOid::from(unsafe {
+            pg_sys::SPI_gettypeid(self.tupdesc?, ordinal)
+        }))
+    }
+
+    /// The 1-based ordinal position of the column with the specified name, or `None` if there's
+    /// no such column
+    pub fn column_ordinal(&self, name: &str) -> Option<i32> {
+        let name = std::ffi::CString::new(name).expect("column name contained a null byte");
+        match unsafe { pg_sys::SPI_fnumber(self.tupdesc?, name.as_ptr()) } {
+            // system columns have negative attribute numbers, and aren't part of the result
+            ordinal if ordinal > 0 => Some(ordinal),
+            _ => None,
+        }
+    }
+
+    /// Get a typed value from the column of the specified name in the current row.
+    ///
+    /// Returns [`SpiRowError::MissingColumn`] if the query has no column of that name
+    pub fn get_by_name<T: FromDatum>(
+        &self,
+        name: &str,
+    ) -> std::result::Result<Option<T>, SpiRowError> {
+        match self.column_ordinal(name) {
+            Some(ordinal) => Ok(self.get_datum(ordinal)),
+            None => Err(SpiRowError::MissingColumn {
+                column: name.to_string(),
+            }),
+        }
+    }
+
+    /// Get the value of the column of the specified name in the current row as text, as
+    /// formatted by its type's output function, whatever that type is.
+    ///
+    /// Returns [`SpiRowError::MissingColumn`] if the query has no column of that name
+    pub fn get_text_by_name(&self, name: &str) -> std::result::Result<Option<String>, SpiRowError> {
+        let ordinal = self
+            .column_ordinal(name)
+            .ok_or_else(|| SpiRowError::MissingColumn {
+                column: name.to_string(),
+            })?;
+        if self.current < 0 {
+            panic!("SpiTupleTable positioned before start")
+        }
+        if self.current as usize >= self.size {
+            return Ok(None);
+        }
+
+        Ok(self.text_at(self.current as usize, ordinal))
+    }
+
+    /// Every row of the result, with the value of each column as text, as formatted by its
+    /// type's output function.  NULLs are `None`.
+    ///
+    /// This doesn't depend on, or move, the current row.
+    pub fn get_text_rows(&self) -> Vec<Vec<Option<String>>> {
+        let columns = self.column_count() as i32;
+        (0..self.size)
+            .map(|row| {
+                (1..=columns)
+                    .map(|ordinal| self.text_at(row, ordinal))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The value of the column at the 1-based `ordinal` of the 0-based `row`, as text
+    fn text_at(&self, row: usize, ordinal: i32) -> Option<String> {
+        unsafe {
+            let heap_tuple = std::slice::from_raw_parts((*self.table).vals, self.size)[row];
+            let value = pg_sys::SPI_getvalue(heap_tuple, self.tupdesc?, ordinal);
+            if value.is_null() {
+                return None;
+            }
+
+            let result = std::ffi::CStr::from_ptr(value)
+                .to_string_lossy()
+                .into_owned();
+            pg_sys::pfree(value as crate::void_mut_ptr);
+            Some(result)
+        }
+    }
+
     pub fn get_one<A: FromDatum>(&self) -> Option<A> {
         self.get_datum(1)
     }
@@ -409,6 +1202,15 @@ impl SpiTupleTable {
         (a, b, c)
     }
 
+    /// Convert every row into a `T`, failing on the first row which can't be.
+    ///
+    /// Like any value read from SPI, pass-by-reference fields such as `&str` are only valid until
+    /// SPI is disconnected.
+    pub fn into_typed<T: SpiRow>(mut self) -> std::result::Result<Vec<T>, SpiRowError> {
+        self.current = -1;
+        self.map(|row| T::from_spi_row(&row)).collect()
+    }
+
     pub fn get_heap_tuple(&self) -> Option<SpiHeapTupleData> {
         if self.current < 0 {
             panic!("SpiTupleTable positioned before start")
@@ -522,6 +1324,37 @@ impl SpiHeapTupleData {
         }
     }
 
+    /// Get a typed Datum value from this HeapTuple by its field name, checking that the field
+    /// exists and that its SQL type is `T`'s.
+    ///
+    /// `Ok(None)` means the field is NULL.
+    pub fn try_by_name<T: FromDatum + IntoDatum>(
+        &self,
+        name: &str,
+    ) -> std::result::Result<Option<T>, SpiRowError> {
+        let entry = self.by_name(name).map_err(|_| SpiRowError::MissingColumn {
+            column: name.to_string(),
+        })?;
+
+        if entry.type_oid != T::type_oid() {
+            let type_name = |oid| unsafe {
+                let name = pg_sys::format_type_be(oid);
+                let result = std::ffi::CStr::from_ptr(name)
+                    .to_string_lossy()
+                    .into_owned();
+                pg_sys::pfree(name as crate::void_mut_ptr);
+                result
+            };
+            return Err(SpiRowError::TypeMismatch {
+                column: name.to_string(),
+                expected: type_name(T::type_oid()),
+                actual: type_name(entry.type_oid),
+            });
+        }
+
+        Ok(entry.value())
+    }
+
     /// Get a mutable typed Datum value from this HeapTuple by its ordinal position.  
     ///
     /// The ordinal position is 1-based.
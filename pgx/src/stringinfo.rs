@@ -155,6 +155,20 @@ impl StringInfo {
         unsafe { pg_sys::enlargeStringInfo(self.sid, needed as i32) }
     }
 
+    /// Ensure that at least `additional` more bytes can be pushed onto the end without
+    /// Postgres having to reallocate the underlying buffer
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.enlarge(additional)
+    }
+
+    /// How many bytes, including the trailing null byte, can be held without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        // safe:  self.sid will never be null
+        unsafe { &mut *self.sid }.maxlen as usize
+    }
+
     /// A pointer representation
     #[inline]
     pub fn as_ptr(&self) -> *mut std::os::raw::c_char {
@@ -175,6 +189,12 @@ impl StringInfo {
         }
     }
 
+    /// A `&str` representation, or an error if the contents aren't valid UTF-8
+    #[inline]
+    pub fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(self.as_bytes())
+    }
+
     /// A mutable `&[u8]` byte slice representation
     #[inline]
     pub fn as_bytes_mut(&mut self) -> &mut [u8] {
@@ -159,6 +159,27 @@ impl<'a> PgTupleDesc<'a> {
         }
     }
 
+    /// Look up the `TupleDesc` of the composite type named `name`, which may be schema-qualified.
+    ///
+    /// Returns `None` if there's no such type, or if it isn't a composite type.  The returned
+    /// TupleDesc is a copy, and is `pfree()`'d when dropped.
+    pub fn for_composite_type(name: &str) -> Option<PgTupleDesc<'static>> {
+        let name = std::ffi::CString::new(name).expect("type name contained a null byte");
+        let mut typoid = pg_sys::InvalidOid;
+        let mut typmod = -1;
+
+        unsafe {
+            pg_sys::parseTypeString(name.as_ptr(), &mut typoid, &mut typmod, true);
+            if typoid == pg_sys::InvalidOid || !pg_sys::type_is_rowtype(typoid) {
+                return None;
+            }
+
+            Some(PgTupleDesc::from_pg_is_copy(
+                pg_sys::lookup_rowtype_tupdesc_copy(typoid, typmod),
+            ))
+        }
+    }
+
     /// From which relation was this TupleDesc created, if any?
     pub fn parent(&self) -> Option<&PgRelation> {
         self.parent
@@ -309,3 +330,17 @@ impl<'a> Iterator for TupleDescDataIntoIterator<'a> {
         Some(result)
     }
 }
+
+/// Look up the [`PgTupleDesc`] of a composite type by name, panicking if it doesn't exist.
+///
+/// ```rust,no_run
+/// use pgx::{composite_type, PgTupleDesc};
+/// let tupdesc: PgTupleDesc = composite_type!("my_schema.my_type");
+/// ```
+#[macro_export]
+macro_rules! composite_type {
+    ($name:expr) => {
+        $crate::PgTupleDesc::for_composite_type($name)
+            .unwrap_or_else(|| panic!("could not find composite type `{}`", $name))
+    };
+}
@@ -0,0 +1,196 @@
+// Copyright 2020 ZomboDB, LLC <zombodb@gmail.com>. All rights reserved. Use of this source code is
+// governed by the MIT license that can be found in the LICENSE file.
+
+//! Provides a safe wrapper around the `pg_sys::WindowObject` Postgres passes to window functions
+use crate::{get_getarg_type, is_a, pg_sys, FromDatum};
+use std::mem::MaybeUninit;
+
+/// Where the row offset given to [`PgWindowObject::get_func_arg_in_partition`] and
+/// [`PgWindowObject::get_func_arg_in_frame`] is relative to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowSeek {
+    /// The current row
+    Current,
+    /// The first row of the partition or frame
+    Head,
+    /// The last row of the partition or frame
+    Tail,
+}
+
+impl WindowSeek {
+    fn as_seektype(self) -> std::os::raw::c_int {
+        (match self {
+            WindowSeek::Current => pg_sys::WINDOW_SEEK_CURRENT,
+            WindowSeek::Head => pg_sys::WINDOW_SEEK_HEAD,
+            WindowSeek::Tail => pg_sys::WINDOW_SEEK_TAIL,
+        }) as std::os::raw::c_int
+    }
+}
+
+/// The state of a `#[pg_extern(window)]` function's partition, as allocated by Postgres.  It's
+/// zeroed, so `initialized` starts out `false`.
+#[repr(C)]
+struct PartitionLocal<T> {
+    initialized: bool,
+    value: MaybeUninit<T>,
+}
+
+/// The window being computed by a `#[pg_extern(window)]` function, as with Postgres'
+/// `PG_WINDOW_OBJECT()`.
+///
+/// The function gets at it by taking a `fcinfo: pg_sys::FunctionCallInfo` as its last argument.
+pub struct PgWindowObject {
+    fcinfo: pg_sys::FunctionCallInfo,
+    winobj: pg_sys::WindowObject,
+}
+
+impl PgWindowObject {
+    /// The window object of the call described by `fcinfo`, or `None` if the function isn't being
+    /// called as a window function.
+    ///
+    /// ## Safety
+    ///
+    /// The provided `fcinfo` must be valid, and there must only be one `PgWindowObject` for it at a
+    /// time, as [`PgWindowObject::partition_local_memory`] hands out a mutable reference.
+    pub unsafe fn from_fcinfo(fcinfo: pg_sys::FunctionCallInfo) -> Option<Self> {
+        let context = fcinfo.as_ref().unwrap().context;
+        if is_a(context, pg_sys::NodeTag_T_WindowObjectData) {
+            Some(PgWindowObject {
+                fcinfo,
+                winobj: context as pg_sys::WindowObject,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The position of the current row within its partition, counting from zero
+    pub fn current_position(&self) -> i64 {
+        unsafe { pg_sys::WinGetCurrentPosition(self.winobj) }
+    }
+
+    /// The number of rows in the current partition
+    ///
+    /// Postgres has to read the whole partition to find this out, so it's best avoided by
+    /// functions which otherwise only look at the current row or those before it.
+    pub fn partition_row_count(&self) -> i64 {
+        unsafe { pg_sys::WinGetPartitionRowCount(self.winobj) }
+    }
+
+    /// Tell Postgres the function will never again need to look at rows before `markpos`, so it
+    /// can discard them.
+    pub fn set_mark_position(&self, markpos: i64) {
+        unsafe { pg_sys::WinSetMarkPosition(self.winobj, markpos) }
+    }
+
+    /// Are the rows at `pos1` and `pos2` peers, that is, equal according to the window's `ORDER BY`?
+    ///
+    /// Every row is a peer of every other row when the window has no `ORDER BY`.
+    pub fn rows_are_peers(&self, pos1: i64, pos2: i64) -> bool {
+        unsafe { pg_sys::WinRowsArePeers(self.winobj, pos1, pos2) }
+    }
+
+    /// State which lasts for the whole of the current partition, starting out as `T::default()`
+    /// for the partition's first row.
+    ///
+    /// Postgres frees the memory at the end of the partition without running any destructors,
+    /// so `T` must be `Copy`.  Every call for the same function must use the same `T`.
+    pub fn partition_local_memory<T: Copy + Default>(&mut self) -> &mut T {
+        unsafe {
+            let local = pg_sys::WinGetPartitionLocalMemory(
+                self.winobj,
+                std::mem::size_of::<PartitionLocal<T>>(),
+            ) as *mut PartitionLocal<T>;
+            let local = local.as_mut().unwrap();
+            if !local.initialized {
+                local.value = MaybeUninit::new(T::default());
+                local.initialized = true;
+            }
+            &mut *local.value.as_mut_ptr()
+        }
+    }
+
+    /// The value of the function's argument `argno` (counting from zero) for the row `relpos`
+    /// rows from `seek`, within the current partition.
+    ///
+    /// Returns `None` if the argument is NULL, or there's no such row.  If `set_mark` is true, and
+    /// the row exists, the mark is moved to it, as with [`PgWindowObject::set_mark_position`].
+    pub fn get_func_arg_in_partition<T: FromDatum>(
+        &self,
+        argno: usize,
+        relpos: i32,
+        seek: WindowSeek,
+        set_mark: bool,
+    ) -> Option<T> {
+        let mut isnull = false;
+        let mut isout = false;
+        unsafe {
+            let datum = pg_sys::WinGetFuncArgInPartition(
+                self.winobj,
+                argno as std::os::raw::c_int,
+                relpos,
+                seek.as_seektype(),
+                set_mark,
+                &mut isnull,
+                &mut isout,
+            );
+            self.arg_from_datum(argno, datum, isnull || isout)
+        }
+    }
+
+    /// The value of the function's argument `argno` (counting from zero) for the row `relpos`
+    /// rows from `seek`, within the current row's window frame.
+    ///
+    /// Returns `None` if the argument is NULL, or there's no such row.  If `set_mark` is true, and
+    /// the row exists, the mark is moved to it, as with [`PgWindowObject::set_mark_position`].
+    pub fn get_func_arg_in_frame<T: FromDatum>(
+        &self,
+        argno: usize,
+        relpos: i32,
+        seek: WindowSeek,
+        set_mark: bool,
+    ) -> Option<T> {
+        let mut isnull = false;
+        let mut isout = false;
+        unsafe {
+            let datum = pg_sys::WinGetFuncArgInFrame(
+                self.winobj,
+                argno as std::os::raw::c_int,
+                relpos,
+                seek.as_seektype(),
+                set_mark,
+                &mut isnull,
+                &mut isout,
+            );
+            self.arg_from_datum(argno, datum, isnull || isout)
+        }
+    }
+
+    /// The value of the function's argument `argno` (counting from zero) for the current row,
+    /// or `None` if it's NULL
+    pub fn get_func_arg_current<T: FromDatum>(&self, argno: usize) -> Option<T> {
+        let mut isnull = false;
+        unsafe {
+            let datum = pg_sys::WinGetFuncArgCurrent(
+                self.winobj,
+                argno as std::os::raw::c_int,
+                &mut isnull,
+            );
+            self.arg_from_datum(argno, datum, isnull)
+        }
+    }
+
+    unsafe fn arg_from_datum<T: FromDatum>(
+        &self,
+        argno: usize,
+        datum: pg_sys::Datum,
+        is_null: bool,
+    ) -> Option<T> {
+        let typoid = if T::NEEDS_TYPID {
+            get_getarg_type(self.fcinfo, argno)
+        } else {
+            pg_sys::InvalidOid
+        };
+        T::from_datum(datum, is_null, typoid)
+    }
+}
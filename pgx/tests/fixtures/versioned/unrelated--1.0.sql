@@ -0,0 +1 @@
+CREATE FUNCTION unrelated() RETURNS int LANGUAGE sql AS 'SELECT 0';
@@ -0,0 +1 @@
+CREATE FUNCTION versioned_two() RETURNS int LANGUAGE sql AS 'SELECT 2';
@@ -0,0 +1 @@
+CREATE FUNCTION versioned_one() RETURNS int LANGUAGE sql AS 'SELECT 1';
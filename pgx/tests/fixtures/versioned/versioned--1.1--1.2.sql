@@ -0,0 +1 @@
+DROP FUNCTION versioned_one();